@@ -0,0 +1,71 @@
+//! Compares response buffers under concurrent Set/Get.
+//!
+//! cargo bench --bench res_buffer
+//!
+//! The number of shards defaults to the available parallelism(env: RES_BUFFER_SHARDS).
+
+use core::time::Duration;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rs_perf_test_helper::tonic;
+use tonic::Request;
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::res::sharded::svc::sharded_res_buffer_service_new;
+
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::{get, set_req};
+
+const GET_TIMEOUT: Duration = Duration::from_secs(10);
+const TASKS: usize = 64;
+const PAIRS_PER_TASK: usize = 2000;
+
+async fn set_get<B>(buf: Arc<B>)
+where
+    B: ResBufferService,
+{
+    for _ in 0..PAIRS_PER_TASK {
+        let reply_id: Uuid = Uuid::generate();
+        buf.set(Request::new(set_req(reply_id))).await.unwrap();
+        get(buf.as_ref(), reply_id, GET_TIMEOUT).await.unwrap();
+    }
+}
+
+async fn run<B>(name: &str, buf: B)
+where
+    B: ResBufferService,
+{
+    let buf: Arc<B> = Arc::new(buf);
+    let started: Instant = Instant::now();
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|_| tokio::spawn(set_get(buf.clone())))
+        .collect();
+    for t in tasks {
+        t.await.unwrap();
+    }
+    let elapsed: Duration = started.elapsed();
+    let pairs: f64 = (TASKS * PAIRS_PER_TASK) as f64;
+    println!(
+        "{name:>10}: {elapsed:>12.3?} {:>12.0} set/get pairs/s",
+        pairs / elapsed.as_secs_f64()
+    );
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let shards: usize = std::env::var("RES_BUFFER_SHARDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(4);
+    run("btree", res_buffer_service_new(TASKS).await).await;
+    let sharded = sharded_res_buffer_service_new(shards, TASKS).await.unwrap();
+    run(&format!("sharded({shards})"), sharded).await;
+}
@@ -1,9 +1,11 @@
+use std::env;
 use std::io;
 
 fn main() -> Result<(), io::Error> {
+    let client: bool = env::var_os("CARGO_FEATURE_CLIENT").is_some();
     tonic_build::configure()
         .build_server(true)
-        .build_client(false)
+        .build_client(client)
         .compile(
             &[
                 "perf/helper/proto/common/v1/uuid.proto",
@@ -11,6 +13,7 @@ fn main() -> Result<(), io::Error> {
                 "perf/helper/proto/direct/v1/helper.proto",
                 "perf/helper/proto/indirect/v1/helper.proto",
                 "perf/helper/proto/buffer/v1/helper.proto",
+                "perf/helper/proto/buffer/v1/wal.proto",
             ],
             &["rs-perf-helper-proto"],
         )?;
@@ -0,0 +1,58 @@
+use core::time::Duration;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+
+pub const DEPTH_DEFAULT: usize = 1;
+
+/// What a buffer does with a new entry when it is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FullPolicy {
+    /// Rejects the entry(`Unavailable`).
+    #[default]
+    Reject,
+
+    /// Waits until the buffer has room; rejects the entry if the duration elapses.
+    Block(Duration),
+
+    /// Drops the oldest entry to make room.
+    DropOldest,
+}
+
+/// Settings of an actor: the depth of its request channel and its full buffer policy.
+#[derive(Clone, Copy, Debug)]
+pub struct ActorConfig {
+    depth: usize,
+    full: FullPolicy,
+}
+
+impl Default for ActorConfig {
+    fn default() -> Self {
+        Self {
+            depth: DEPTH_DEFAULT,
+            full: FullPolicy::default(),
+        }
+    }
+}
+
+impl ActorConfig {
+    /// Creates a config; the depth is at least 1.
+    pub fn new(depth: usize, full: FullPolicy) -> Self {
+        Self {
+            depth: depth.max(1),
+            full,
+        }
+    }
+
+    pub fn as_depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn as_full_policy(&self) -> FullPolicy {
+        self.full
+    }
+
+    /// Creates the request channel of the actor.
+    pub fn channel<T>(&self) -> (Sender<T>, Receiver<T>) {
+        tokio::sync::mpsc::channel(self.depth)
+    }
+}
@@ -0,0 +1,60 @@
+use core::time::Duration;
+use std::env;
+
+use rs_perf_test_helper::client::convert::ConvertClient;
+use rs_perf_test_helper::loadgen::closed::Limit;
+use rs_perf_test_helper::loadgen::report::Report;
+use rs_perf_test_helper::loadgen::seed::Seed;
+use rs_perf_test_helper::loadgen::{closed, open};
+
+const TARGET_ADDR_DEFAULT: &str = "http://127.0.0.1:50051";
+const TOTAL_REQUESTS_DEFAULT: u64 = 1000;
+
+fn env_parse<T>(key: &str) -> Result<Option<T>, String>
+where
+    T: core::str::FromStr,
+    T::Err: core::fmt::Display,
+{
+    env::var(key)
+        .ok()
+        .map(|s: String| str::parse(s.as_str()).map_err(|e| format!("invalid {key}: {e}")))
+        .transpose()
+}
+
+fn limit() -> Result<Limit, String> {
+    let duration_ms: Option<u64> = env_parse("ENV_DURATION_MS")?;
+    let total: Option<u64> = env_parse("ENV_TOTAL_REQUESTS")?;
+    Ok(match (duration_ms, total) {
+        (Some(ms), _) => Limit::Duration(Duration::from_millis(ms)),
+        (None, Some(n)) => Limit::Requests(n),
+        (None, None) => Limit::Requests(TOTAL_REQUESTS_DEFAULT),
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), String> {
+    let target: String = env::var("ENV_TARGET_ADDR")
+        .ok()
+        .unwrap_or_else(|| TARGET_ADDR_DEFAULT.into());
+    let concurrency: usize = env_parse("ENV_CONCURRENCY")?.unwrap_or(1);
+    let seed: Seed = env_parse("ENV_SEED")?.unwrap_or_default();
+    let rate: Option<f64> = env_parse("ENV_RATE")?;
+
+    let client: ConvertClient = ConvertClient::connect(target)
+        .await
+        .map_err(|e| format!("{e}"))?;
+
+    let report: Report = match rate {
+        None => {
+            let cfg = closed::Config::new(concurrency, limit()?, seed);
+            closed::run(client, &cfg).await
+        }
+        Some(r) => {
+            let cfg = open::Config::new(r, limit()?, seed).map_err(|e| format!("{e}"))?;
+            open::run(client, &cfg).await
+        }
+    }
+    .map_err(|e| format!("{e}"))?;
+    print!("{report}");
+    Ok(())
+}
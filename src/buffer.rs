@@ -2,4 +2,8 @@ pub mod res;
 
 pub mod cmd;
 
+pub mod blocked;
+pub mod full;
+
+pub mod file;
 pub mod vecdeque;
@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+
+use tokio::time::Instant;
+
+/// Entries waiting for room in a full buffer(oldest first).
+pub struct Blocked<T> {
+    q: VecDeque<(Instant, T)>,
+}
+
+impl<T> Default for Blocked<T> {
+    fn default() -> Self {
+        Self { q: VecDeque::new() }
+    }
+}
+
+impl<T> Blocked<T> {
+    pub fn park(&mut self, deadline: Instant, t: T) {
+        self.q.push_back((deadline, t));
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.q.pop_front().map(|p| p.1)
+    }
+
+    /// The earliest deadline of the entries.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.q.iter().map(|p| p.0).min()
+    }
+
+    /// Removes entries whose deadlines passed.
+    pub fn expired(&mut self, now: Instant) -> Vec<T> {
+        let (expired, waiting): (VecDeque<_>, VecDeque<_>) =
+            self.q.drain(..).partition(|p| p.0 <= now);
+        self.q = waiting;
+        expired.into_iter().map(|p| p.1).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.q.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.q.is_empty()
+    }
+}
@@ -1,2 +1,5 @@
+pub mod ack;
+pub mod dead;
 pub mod load;
 pub mod save;
+pub mod stats;
@@ -0,0 +1,77 @@
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::req_buf::{AckRequest, NackRequest, RejectRequest};
+use helper::proto::common::v1::Uuid as Cuid;
+
+/// A checked ack(or nack) of a delivery.
+pub struct AckReq {
+    request_id: Uuid,
+    delivery_id: Uuid,
+}
+
+impl AckReq {
+    pub fn as_request_id(&self) -> Uuid {
+        self.request_id
+    }
+
+    pub fn as_delivery_id(&self) -> Uuid {
+        self.delivery_id
+    }
+
+    fn checked(reqid: Option<&Cuid>, delid: Option<&Cuid>) -> Result<Self, Status> {
+        let request_id: Uuid = reqid
+            .try_into()
+            .map_err(|_| Status::invalid_argument("request id missing"))?;
+        let delivery_id: Uuid = delid.try_into().map_err(|_| {
+            Status::invalid_argument(format!("delivery id missing. request id: {request_id}"))
+        })?;
+        Ok(Self {
+            request_id,
+            delivery_id,
+        })
+    }
+}
+
+impl TryFrom<AckRequest> for AckReq {
+    type Error = Status;
+    fn try_from(a: AckRequest) -> Result<Self, Self::Error> {
+        Self::checked(a.request_id.as_ref(), a.delivery_id.as_ref())
+    }
+}
+
+impl TryFrom<NackRequest> for AckReq {
+    type Error = Status;
+    fn try_from(n: NackRequest) -> Result<Self, Self::Error> {
+        Self::checked(n.request_id.as_ref(), n.delivery_id.as_ref())
+    }
+}
+
+/// A checked reject of a delivery.
+pub struct RejectReq {
+    ack: AckReq,
+    reason: String,
+}
+
+impl RejectReq {
+    pub fn as_ack(&self) -> &AckReq {
+        &self.ack
+    }
+
+    pub fn into_reason(self) -> String {
+        self.reason
+    }
+}
+
+impl TryFrom<RejectRequest> for RejectReq {
+    type Error = Status;
+    fn try_from(r: RejectRequest) -> Result<Self, Self::Error> {
+        let ack: AckReq = AckReq::checked(r.request_id.as_ref(), r.delivery_id.as_ref())?;
+        Ok(Self {
+            ack,
+            reason: r.reason,
+        })
+    }
+}
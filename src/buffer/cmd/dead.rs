@@ -0,0 +1,2 @@
+pub mod req;
+pub mod res;
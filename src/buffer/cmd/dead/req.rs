@@ -0,0 +1,71 @@
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::dead_letter::{ListRequest, RequeueRequest};
+
+pub struct ListReq {
+    request_id: Uuid,
+    max_count: usize,
+}
+
+impl ListReq {
+    pub fn as_request_id(&self) -> Uuid {
+        self.request_id
+    }
+
+    /// The max number of entries(0: all).
+    pub fn as_max_count(&self) -> usize {
+        self.max_count
+    }
+}
+
+impl TryFrom<ListRequest> for ListReq {
+    type Error = Status;
+    fn try_from(g: ListRequest) -> Result<Self, Self::Error> {
+        let request_id: Uuid = g
+            .request_id
+            .as_ref()
+            .try_into()
+            .map_err(|_| Status::invalid_argument("request id missing"))?;
+        let max_count: usize = usize::try_from(g.max_count).unwrap_or(usize::MAX);
+        Ok(Self {
+            request_id,
+            max_count,
+        })
+    }
+}
+
+pub struct RequeueReq {
+    request_id: Uuid,
+    reply_id: Uuid,
+}
+
+impl RequeueReq {
+    pub fn as_request_id(&self) -> Uuid {
+        self.request_id
+    }
+
+    pub fn as_reply_id(&self) -> Uuid {
+        self.reply_id
+    }
+}
+
+impl TryFrom<RequeueRequest> for RequeueReq {
+    type Error = Status;
+    fn try_from(g: RequeueRequest) -> Result<Self, Self::Error> {
+        let request_id: Uuid = g
+            .request_id
+            .as_ref()
+            .try_into()
+            .map_err(|_| Status::invalid_argument("request id missing"))?;
+        let reply_id: Uuid = g.reply_id.as_ref().try_into().map_err(|_| {
+            Status::invalid_argument(format!("reply id missing. request id: {request_id}"))
+        })?;
+        Ok(Self {
+            request_id,
+            reply_id,
+        })
+    }
+}
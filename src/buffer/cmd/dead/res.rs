@@ -0,0 +1,29 @@
+use std::time::SystemTime;
+
+use prost_types::Timestamp;
+
+use crate::uuid::Uuid;
+
+use crate::buffer::cmd::save::req::{SaveInfo, SaveReq};
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::dead_letter::Entry;
+
+impl From<SaveInfo> for Entry {
+    fn from(si: SaveInfo) -> Self {
+        let saved: SystemTime = si.as_saved();
+        let delivery_count: u64 = si.as_deliveries();
+        let reason: String = si.as_reason().unwrap_or_default().into();
+        let req: SaveReq = si.into_req();
+        let reply_id: Uuid = req.as_reply_id();
+        let received: Timestamp = req.as_received().clone();
+        Self {
+            req: Some(req.into_request()),
+            reply_id: Some(reply_id.into()),
+            received: Some(received),
+            saved: Some(saved.into()),
+            delivery_count,
+            reason,
+        }
+    }
+}
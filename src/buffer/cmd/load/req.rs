@@ -1,3 +1,5 @@
+use core::time::Duration;
+
 use tonic::Status;
 
 use crate::retry::Retry;
@@ -6,9 +8,14 @@ use crate::uuid::Uuid;
 use crate::rpc::perf::helper;
 use helper::proto::buffer::v1::req_buf::LoadRequest;
 
+/// Larger windows are capped to this.
+pub const WINDOW_MAX: usize = 1024;
+
 pub struct LoadReq {
     request_id: Uuid,
     retry: Retry,
+    max_count: u64,
+    window: usize,
 }
 
 impl LoadReq {
@@ -19,6 +26,27 @@ impl LoadReq {
     pub fn as_retry(&self) -> &Retry {
         &self.retry
     }
+
+    /// Shortens the timeout of the retry to the request deadline(if any).
+    pub fn within(mut self, deadline: Option<Duration>) -> Self {
+        self.retry = self.retry.within(deadline);
+        self
+    }
+
+    /// The max number of requests to be sent(at least 1).
+    pub fn as_max_count(&self) -> u64 {
+        self.max_count
+    }
+
+    /// The max number of requests sent but not yet read(1 ..= min(max count, [`WINDOW_MAX`])).
+    pub fn as_window(&self) -> usize {
+        self.window
+    }
+
+    /// Checks if the load keeps the stream open for many requests.
+    pub fn is_streaming(&self) -> bool {
+        1 < self.max_count
+    }
 }
 
 impl TryFrom<LoadRequest> for LoadReq {
@@ -32,6 +60,15 @@ impl TryFrom<LoadRequest> for LoadReq {
         let retry: Retry = g.retry.as_ref().try_into().map_err(|_| {
             Status::invalid_argument(format!("retry missing. request id: {request_id}"))
         })?;
-        Ok(Self { request_id, retry })
+        let max_count: u64 = g.max_count.max(1);
+        let window: usize = usize::try_from(g.window.min(max_count))
+            .unwrap_or(WINDOW_MAX)
+            .clamp(1, WINDOW_MAX);
+        Ok(Self {
+            request_id,
+            retry,
+            max_count,
+            window,
+        })
     }
 }
@@ -0,0 +1,61 @@
+use std::time::SystemTime;
+
+use prost_types::Timestamp;
+
+use crate::uuid::Uuid;
+
+use crate::buffer::cmd::save::req::{SaveInfo, SaveReq};
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::req_buf::LoadResponse;
+
+/// A request handed to a load.
+pub struct Delivery {
+    id: Option<Uuid>,
+    info: SaveInfo,
+}
+
+impl Delivery {
+    /// Creates a delivery of the request(id: set if an ack is expected).
+    pub fn new(id: Option<Uuid>, info: SaveInfo) -> Self {
+        Self { id, info }
+    }
+
+    pub fn as_id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// The number of deliveries including this one.
+    pub fn as_count(&self) -> u64 {
+        self.info.as_deliveries() + 1
+    }
+
+    pub fn as_info(&self) -> &SaveInfo {
+        &self.info
+    }
+
+    pub fn into_info(self) -> SaveInfo {
+        self.info
+    }
+}
+
+impl From<Delivery> for LoadResponse {
+    fn from(d: Delivery) -> Self {
+        let delivery_id: Option<Uuid> = d.as_id();
+        let delivery_count: u64 = d.as_count();
+        let si: SaveInfo = d.into_info();
+        let saved: SystemTime = si.as_saved();
+        let req: SaveReq = si.into_req();
+        let reply_id: Uuid = req.as_reply_id();
+        let received: Timestamp = req.as_received().clone();
+        Self {
+            req: Some(req.into_request()),
+            reply_id: Some(reply_id.into()),
+            received: Some(received),
+            saved: Some(saved.into()),
+            loaded: Some(SystemTime::now().into()),
+            delivery_id: delivery_id.map(|u| u.into()),
+            delivery_count,
+        }
+    }
+}
@@ -10,6 +10,7 @@ use crate::rpc::perf::helper;
 use helper::proto::buffer::v1::req_buf::SaveRequest;
 use helper::proto::direct::v1::conv_svc::ConvertRequest;
 
+#[derive(Clone)]
 pub struct SaveReq {
     request_id: Uuid,
     reply_id: Uuid,
@@ -61,19 +62,66 @@ impl TryFrom<SaveRequest> for SaveReq {
     }
 }
 
+#[derive(Clone)]
 pub struct SaveInfo {
     req: SaveReq,
     saved: SystemTime,
+    deliveries: u64,
+    reason: Option<String>,
 }
 
 impl SaveInfo {
     pub fn new(req: SaveReq, saved: SystemTime) -> Self {
-        Self { req, saved }
+        Self {
+            req,
+            saved,
+            deliveries: 0,
+            reason: None,
+        }
     }
 
     pub fn as_saved(&self) -> SystemTime {
         self.saved
     }
+
+    /// The number of deliveries so far.
+    pub fn as_deliveries(&self) -> u64 {
+        self.deliveries
+    }
+
+    /// Counts a delivery.
+    pub fn delivered(self) -> Self {
+        Self {
+            deliveries: self.deliveries + 1,
+            ..self
+        }
+    }
+
+    /// Why this request was moved to the dead letter buffer.
+    pub fn as_reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub fn rejected(self, reason: String) -> Self {
+        Self {
+            reason: Some(reason),
+            ..self
+        }
+    }
+
+    /// Resets the deliveries and the reason to be delivered again.
+    pub fn revived(self) -> Self {
+        Self {
+            deliveries: 0,
+            reason: None,
+            ..self
+        }
+    }
+
+    pub fn as_req(&self) -> &SaveReq {
+        &self.req
+    }
+
     pub fn into_req(self) -> SaveReq {
         self.req
     }
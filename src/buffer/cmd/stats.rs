@@ -0,0 +1,61 @@
+use core::time::Duration;
+
+use crate::retry::budget;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::req_buf::StatsResponse;
+
+pub struct ReqStats {
+    capacity: u64,
+    pending: u64,
+    oldest_age: Option<Duration>,
+    in_flight: u64,
+    dropped: u64,
+}
+
+impl ReqStats {
+    pub fn new(capacity: u64, pending: u64, oldest_age: Option<Duration>, in_flight: u64) -> Self {
+        Self {
+            capacity,
+            pending,
+            oldest_age,
+            in_flight,
+            dropped: 0,
+        }
+    }
+
+    /// Sets the number of dead requests dropped.
+    pub fn dropped(mut self, dropped: u64) -> Self {
+        self.dropped = dropped;
+        self
+    }
+
+    pub fn as_capacity(&self) -> u64 {
+        self.capacity
+    }
+    pub fn as_pending(&self) -> u64 {
+        self.pending
+    }
+    pub fn as_oldest_age(&self) -> Option<Duration> {
+        self.oldest_age
+    }
+    pub fn as_in_flight(&self) -> u64 {
+        self.in_flight
+    }
+    pub fn as_dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl From<ReqStats> for StatsResponse {
+    fn from(d: ReqStats) -> Self {
+        Self {
+            capacity: d.capacity,
+            pending: d.pending,
+            oldest_age: d.oldest_age.and_then(|a| a.try_into().ok()),
+            in_flight: d.in_flight,
+            dropped: d.dropped,
+            retries: Some(budget::shared().snapshot().into()),
+        }
+    }
+}
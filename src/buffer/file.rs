@@ -0,0 +1,2 @@
+pub mod svc;
+pub mod wal;
@@ -0,0 +1,281 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use futures::StreamExt;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::ReceiverStream;
+
+use tonic::{Request, Response, Status};
+
+use crate::actor::{ActorConfig, FullPolicy};
+use crate::uuid::Uuid;
+
+use crate::buffer::cmd::save::req::{SaveInfo, SaveReq};
+use crate::buffer::file::wal::Wal;
+use crate::buffer::vecdeque::svc::{buf_svc_st_new, BufSvcSt};
+
+use crate::rpc::perf::helper;
+
+use helper::proto::buffer::v1::req_buf::{AckRequest, AckResponse};
+use helper::proto::buffer::v1::req_buf::{LoadRequest, LoadResponse};
+use helper::proto::buffer::v1::req_buf::{NackRequest, NackResponse};
+use helper::proto::buffer::v1::req_buf::{RejectRequest, RejectResponse};
+use helper::proto::buffer::v1::req_buf::{SaveRequest, SaveResponse};
+use helper::proto::buffer::v1::req_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::req_wal::Saved;
+
+pub enum Req {
+    Saved(Saved, oneshot::Sender<Result<(), Status>>),
+    Loaded(Uuid, oneshot::Sender<Result<(), Status>>),
+}
+
+impl Req {
+    fn handle(wal: &mut Wal, req: Req) {
+        let (r, reply) = match req {
+            Req::Saved(s, reply) => (wal.append_saved(s), reply),
+            Req::Loaded(reply_id, reply) => (wal.append_loaded(reply_id), reply),
+        };
+        if reply.send(r).is_err() {
+            log::warn!("Unable to send an append evt");
+        }
+    }
+}
+
+/// Opens the wal and appends records on a dedicated thread(file io blocks).
+fn wal_new(
+    path: PathBuf,
+    compact_threshold: u64,
+    mut rx: Receiver<Req>,
+    opened: oneshot::Sender<Result<Vec<Saved>, Status>>,
+) {
+    std::thread::spawn(move || {
+        let mut wal: Wal = match Wal::open(path, compact_threshold) {
+            Ok(w) => w,
+            Err(e) => {
+                let _ = opened.send(Err(e));
+                return;
+            }
+        };
+        let live: Vec<Saved> = wal.as_live().iter().cloned().collect();
+        if opened.send(Ok(live)).is_err() {
+            return;
+        }
+        while let Some(req) = rx.blocking_recv() {
+            Req::handle(&mut wal, req);
+        }
+    });
+}
+
+/// A request buffer which logs saved/loaded requests to survive restarts.
+///
+/// Loaded requests are removed from the log; acks are not supported(`Unimplemented`).
+pub struct FileBufSvc {
+    wal: Sender<Req>,
+    inner: BufSvcSt,
+}
+
+impl FileBufSvc {
+    async fn append<F>(wal: &Sender<Req>, req: F) -> Result<(), Status>
+    where
+        F: FnOnce(oneshot::Sender<Result<(), Status>>) -> Req,
+    {
+        let (tx, rx) = oneshot::channel();
+        wal.send(req(tx))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send an append request: {e}")))?;
+        rx.await.map_err(|_| Status::internal("Unable to append"))?
+    }
+
+    pub async fn saved(wal: &Sender<Req>, s: Saved) -> Result<(), Status> {
+        Self::append(wal, |tx| Req::Saved(s, tx)).await
+    }
+
+    pub async fn loaded(wal: &Sender<Req>, reply_id: Uuid) -> Result<(), Status> {
+        Self::append(wal, |tx| Req::Loaded(reply_id, tx)).await
+    }
+
+    /// Puts the replayed requests to the buffer; requests which do not fit are dropped.
+    async fn restore(&self, live: Vec<Saved>) -> Result<(), Status> {
+        for s in live {
+            let saved: SystemTime = s
+                .saved
+                .clone()
+                .and_then(|t| SystemTime::try_from(t).ok())
+                .unwrap_or_else(SystemTime::now);
+            let checked: SaveReq = s
+                .req
+                .ok_or_else(|| Status::internal("request missing in the wal"))?
+                .try_into()?;
+            let reply_id: Uuid = checked.as_reply_id();
+            let si: SaveInfo = SaveInfo::new(checked, saved);
+            if let Err(e) = BufSvcSt::save(self.inner.as_sender(), si).await {
+                log::warn!("dropped a replayed request. reply id: {reply_id}: {e}");
+                Self::loaded(&self.wal, reply_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts a loaded request back to the saved one.
+    fn unload(lr: LoadResponse) -> Result<SaveInfo, Status> {
+        let saved: SystemTime = lr
+            .saved
+            .and_then(|t| SystemTime::try_from(t).ok())
+            .unwrap_or_else(SystemTime::now);
+        let sr = SaveRequest {
+            request_id: Some(Uuid::generate().into()),
+            reply_id: lr.reply_id,
+            req: lr.req,
+            received: lr.received,
+        };
+        let checked: SaveReq = sr.try_into()?;
+        Ok(SaveInfo::new(checked, saved))
+    }
+
+    /// Puts back loaded requests which were not sent(in order, ahead of the others).
+    ///
+    /// The requests are replayed after a restart if they could not be put back.
+    async fn requeue(inner: &BufSvcSt, v: Vec<LoadResponse>) {
+        let mut back: Vec<SaveInfo> = Vec::with_capacity(v.len());
+        for lr in v {
+            let reply_id: Option<Uuid> = lr.reply_id.as_ref().map(Uuid::from);
+            match Self::unload(lr) {
+                Ok(si) => back.push(si),
+                Err(e) => log::warn!("Unable to put back a request. reply id: {reply_id:?}: {e}"),
+            }
+        }
+        if let Err(e) = BufSvcSt::push_front(inner.as_sender(), back).await {
+            log::warn!("Unable to put back requests: {e}");
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ReqBufferService for FileBufSvc {
+    type LoadStream = ReceiverStream<Result<LoadResponse, Status>>;
+
+    async fn save(&self, req: Request<SaveRequest>) -> Result<Response<SaveResponse>, Status> {
+        let sr: SaveRequest = req.into_inner();
+        let checked: SaveReq = sr.clone().try_into()?;
+        let reply_id: Uuid = checked.as_reply_id();
+        let saved: SystemTime = SystemTime::now();
+        let s = Saved {
+            req: Some(sr),
+            saved: Some(saved.into()),
+        };
+        Self::saved(&self.wal, s).await?;
+        let si: SaveInfo = SaveInfo::new(checked, saved);
+        if let Err(e) = BufSvcSt::save(self.inner.as_sender(), si).await {
+            if let Err(e) = Self::loaded(&self.wal, reply_id).await {
+                log::warn!("Unable to remove a rejected request. reply id: {reply_id}: {e}");
+            }
+            return Err(e);
+        }
+        let reply = SaveResponse {
+            saved: Some(saved.into()),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn load(&self, req: Request<LoadRequest>) -> Result<Response<Self::LoadStream>, Status> {
+        let ls = ReqBufferService::load(&self.inner, req).await?.into_inner();
+        let wal: Sender<Req> = self.wal.clone();
+        let inner: BufSvcSt = self.inner.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            let mut ls = Box::pin(ls);
+            while let Some(r) = ls.next().await {
+                // requests loaded but not sent are put back(not logged as loaded)
+                let Ok(permit) = tx.reserve().await else {
+                    log::warn!("Unable to send a reply: the load was cancelled");
+                    ls.close();
+                    let rest = futures::stream::iter([r]).chain(ls);
+                    let back: Vec<LoadResponse> =
+                        rest.filter_map(|r| async { r.ok() }).collect().await;
+                    Self::requeue(&inner, back).await;
+                    return;
+                };
+                if let Ok(lr) = &r {
+                    let reply_id: Option<Uuid> = lr.reply_id.as_ref().map(Uuid::from);
+                    if let Some(reply_id) = reply_id {
+                        if let Err(e) = Self::loaded(&wal, reply_id).await {
+                            log::warn!("Unable to log a load. reply id: {reply_id}: {e}");
+                        }
+                    }
+                }
+                permit.send(r);
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn ack(&self, _req: Request<AckRequest>) -> Result<Response<AckResponse>, Status> {
+        Err(Status::unimplemented(
+            "acks are not supported by the file buffer",
+        ))
+    }
+
+    async fn nack(&self, _req: Request<NackRequest>) -> Result<Response<NackResponse>, Status> {
+        Err(Status::unimplemented(
+            "nacks are not supported by the file buffer",
+        ))
+    }
+
+    async fn reject(
+        &self,
+        _req: Request<RejectRequest>,
+    ) -> Result<Response<RejectResponse>, Status> {
+        Err(Status::unimplemented(
+            "rejects are not supported by the file buffer",
+        ))
+    }
+
+    async fn stats(&self, req: Request<StatsRequest>) -> Result<Response<StatsResponse>, Status> {
+        ReqBufferService::stats(&self.inner, req).await
+    }
+}
+
+/// Creates a buffer which replays the wal at the path; compacted after `compact_threshold`
+/// garbage records.
+pub async fn file_request_buffer_service_new<P>(
+    path: P,
+    max_buf_size: usize,
+    compact_threshold: u64,
+) -> Result<impl ReqBufferService, Status>
+where
+    P: Into<PathBuf>,
+{
+    let cfg = ActorConfig::default();
+    file_request_buffer_service_with_config(&cfg, path, max_buf_size, compact_threshold).await
+}
+
+/// Creates a buffer which replays the wal at the path using the config.
+///
+/// The buffer rejects requests once full whatever the policy of the config: dropped
+/// requests would stay in the wal and blocked saves would stall the replay.
+pub async fn file_request_buffer_service_with_config<P>(
+    cfg: &ActorConfig,
+    path: P,
+    max_buf_size: usize,
+    compact_threshold: u64,
+) -> Result<impl ReqBufferService, Status>
+where
+    P: Into<PathBuf>,
+{
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let (otx, orx) = oneshot::channel();
+    wal_new(path.into(), compact_threshold, rx, otx);
+    let live: Vec<Saved> = orx
+        .await
+        .map_err(|_| Status::internal("Unable to open the wal"))??;
+    let inner_cfg = ActorConfig::new(cfg.as_depth(), FullPolicy::Reject);
+    let svc = FileBufSvc {
+        wal: tx,
+        inner: buf_svc_st_new(&inner_cfg, max_buf_size, None, None).await,
+    };
+    svc.restore(live).await?;
+    Ok(svc)
+}
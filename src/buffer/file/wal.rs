@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::req_wal::record::Record as Kind;
+use helper::proto::buffer::v1::req_wal::{Record, Saved};
+
+fn io2status(e: std::io::Error, msg: &str) -> Status {
+    Status::internal(format!("{msg}: {e}"))
+}
+
+/// Saved requests not yet loaded(oldest first).
+#[derive(Default)]
+pub struct Live {
+    seq: u64,
+    ordered: BTreeMap<u64, Saved>,
+    index: BTreeMap<Uuid, u64>,
+}
+
+impl Live {
+    /// Applies the record; returns the number of records which became garbage.
+    fn apply(&mut self, r: Record) -> u64 {
+        match r.record {
+            None => 1,
+            Some(Kind::Saved(s)) => {
+                let reply_id: Option<Uuid> = s
+                    .req
+                    .as_ref()
+                    .and_then(|q| q.reply_id.as_ref())
+                    .map(Uuid::from);
+                let Some(reply_id) = reply_id else {
+                    return 1;
+                };
+                self.seq += 1;
+                self.ordered.insert(self.seq, s);
+                match self.index.insert(reply_id, self.seq) {
+                    None => 0,
+                    Some(prev) => {
+                        self.ordered.remove(&prev);
+                        1
+                    }
+                }
+            }
+            Some(Kind::Loaded(u)) => {
+                let reply_id: Uuid = u.into();
+                match self.index.remove(&reply_id) {
+                    None => 1,
+                    Some(seq) => {
+                        self.ordered.remove(&seq);
+                        2
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Saved> {
+        self.ordered.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ordered.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ordered.is_empty()
+    }
+}
+
+/// An append only log of length-delimited records; compacted when enough records are garbage.
+///
+/// Records are written to the OS on each append and synced to the disk on compaction.
+pub struct Wal {
+    path: PathBuf,
+    file: File,
+    live: Live,
+    garbage: u64,
+    compact_threshold: u64,
+}
+
+impl Wal {
+    fn replay(path: &Path) -> Result<Live, Status> {
+        let mut live = Live::default();
+        let bytes: Vec<u8> = match fs::read(path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(live),
+            Err(e) => return Err(io2status(e, "Unable to read the wal")),
+        };
+        let mut buf: &[u8] = &bytes;
+        while !buf.is_empty() {
+            match Record::decode_length_delimited(&mut buf) {
+                Ok(r) => {
+                    live.apply(r);
+                }
+                Err(e) => {
+                    // a torn tail of an interrupted append
+                    log::warn!(
+                        "ignored the broken tail of the wal({} bytes): {e}",
+                        buf.len()
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(live)
+    }
+
+    fn append_only(path: &Path) -> Result<File, Status> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| io2status(e, "Unable to open the wal"))
+    }
+
+    /// Opens the wal(created if missing) and replays it.
+    pub fn open<P>(path: P, compact_threshold: u64) -> Result<Self, Status>
+    where
+        P: Into<PathBuf>,
+    {
+        let path: PathBuf = path.into();
+        let live: Live = Self::replay(&path)?;
+        let file: File = Self::append_only(&path)?;
+        let mut wal = Self {
+            path,
+            file,
+            live,
+            garbage: 0,
+            compact_threshold,
+        };
+        wal.compact()?;
+        Ok(wal)
+    }
+
+    pub fn as_live(&self) -> &Live {
+        &self.live
+    }
+
+    fn write(file: &mut File, r: &Record) -> Result<(), Status> {
+        let mut buf: Vec<u8> = Vec::with_capacity(r.encoded_len() + 10);
+        r.encode_length_delimited(&mut buf)
+            .map_err(|e| Status::internal(format!("Unable to encode a record: {e}")))?;
+        file.write_all(&buf)
+            .map_err(|e| io2status(e, "Unable to write a record"))
+    }
+
+    pub fn append(&mut self, r: Record) -> Result<(), Status> {
+        Self::write(&mut self.file, &r)?;
+        self.garbage += self.live.apply(r);
+        match self.compact_threshold <= self.garbage {
+            true => self.compact(),
+            false => Ok(()),
+        }
+    }
+
+    pub fn append_saved(&mut self, s: Saved) -> Result<(), Status> {
+        self.append(Record {
+            record: Some(Kind::Saved(s)),
+        })
+    }
+
+    pub fn append_loaded(&mut self, reply_id: Uuid) -> Result<(), Status> {
+        self.append(Record {
+            record: Some(Kind::Loaded(reply_id.into())),
+        })
+    }
+
+    /// Rewrites the wal using live requests only.
+    pub fn compact(&mut self) -> Result<(), Status> {
+        let tmp: PathBuf = self.path.with_extension("compact");
+        let mut file: File =
+            File::create(&tmp).map_err(|e| io2status(e, "Unable to create a compacted wal"))?;
+        for s in self.live.iter() {
+            let r = Record {
+                record: Some(Kind::Saved(s.clone())),
+            };
+            Self::write(&mut file, &r)?;
+        }
+        file.sync_all()
+            .map_err(|e| io2status(e, "Unable to sync the compacted wal"))?;
+        fs::rename(&tmp, &self.path).map_err(|e| io2status(e, "Unable to replace the wal"))?;
+        self.file = Self::append_only(&self.path)?;
+        self.garbage = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use core::time::Duration;
+
+use prost::Message;
+
+use tonic::codegen::Bytes;
+use tonic::{Code, Status};
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::BufferFull;
+
+/// Why a full buffer rejected an entry; sent as the details of `Unavailable`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Full {
+    size: u64,
+    limit: u64,
+    retry_after: Duration,
+}
+
+impl Full {
+    pub fn new(size: u64, limit: u64, retry_after: Duration) -> Self {
+        Self {
+            size,
+            limit,
+            retry_after,
+        }
+    }
+
+    /// Estimates the time until an entry is removed assuming entries are removed in order:
+    /// the age of the oldest entry divided by the number of entries.
+    pub fn estimate(size: u64, limit: u64, oldest_age: Option<Duration>) -> Self {
+        let per_entry: u32 = size.clamp(1, u32::MAX.into()) as u32;
+        let retry_after: Duration = oldest_age.unwrap_or_default() / per_entry;
+        Self::new(size, limit, retry_after)
+    }
+
+    pub fn as_size(&self) -> u64 {
+        self.size
+    }
+    pub fn as_limit(&self) -> u64 {
+        self.limit
+    }
+    pub fn as_retry_after(&self) -> Duration {
+        self.retry_after
+    }
+
+    /// Gets the details of the `Unavailable` error(if any).
+    pub fn from_status(s: &Status) -> Option<Self> {
+        if s.code() != Code::Unavailable || s.details().is_empty() {
+            return None;
+        }
+        let bf: BufferFull = BufferFull::decode(s.details()).ok()?;
+        let retry_after: Duration = bf
+            .retry_after
+            .and_then(|d| d.try_into().ok())
+            .unwrap_or_default();
+        Some(Self::new(bf.size, bf.limit, retry_after))
+    }
+}
+
+impl From<Full> for BufferFull {
+    fn from(f: Full) -> Self {
+        Self {
+            size: f.size,
+            limit: f.limit,
+            retry_after: f.retry_after.try_into().ok(),
+        }
+    }
+}
+
+impl From<Full> for Status {
+    fn from(f: Full) -> Self {
+        let msg: String = format!(
+            "too many requests. size: {}, limit: {}, retry after: {:?}",
+            f.size, f.limit, f.retry_after
+        );
+        let details: Vec<u8> = BufferFull::from(f).encode_to_vec();
+        Status::with_details(Code::Unavailable, msg, Bytes::from(details))
+    }
+}
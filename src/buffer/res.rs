@@ -1,5 +1,8 @@
 pub mod cmd;
 
 pub mod btree;
+pub mod file;
+pub mod sharded;
+pub mod store;
 
 pub mod expire;
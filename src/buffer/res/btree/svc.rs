@@ -1,20 +1,30 @@
 use core::time::Duration;
-use std::collections::BTreeMap;
-use std::time::{Instant, SystemTime};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 use tokio::sync::mpsc::Sender;
-use tokio::time::Interval;
+use tokio::sync::oneshot;
+use tokio::sync::Notify;
 use tokio_stream::wrappers::ReceiverStream;
 
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
+use tokio::time::Instant;
+
+use crate::actor::{ActorConfig, FullPolicy};
 use crate::uuid::Uuid;
 
+use crate::retry::deadline;
 use crate::retry::Retry;
 
+use crate::buffer::blocked::Blocked;
+use crate::buffer::full::Full;
 use crate::buffer::res::cmd::del::DelReq;
 use crate::buffer::res::cmd::get::GetReq;
 use crate::buffer::res::cmd::set::SetReq;
+use crate::buffer::res::cmd::stats::ResStats;
+use crate::buffer::res::store::{MemStore, Store};
 
 use crate::rpc::perf::helper;
 
@@ -22,56 +32,162 @@ use helper::proto::buffer::v1::res_buf::{DelRequest, DelResponse};
 use helper::proto::buffer::v1::res_buf::{GetRequest, GetResponse};
 use helper::proto::buffer::v1::res_buf::{LenRequest, LenResponse};
 use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
 use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
 
+/// Gets waiting for responses which are not set yet(oldest first).
+pub type Waiters = BTreeMap<Uuid, VecDeque<oneshot::Sender<Result<GetResponse, Status>>>>;
+
+/// Sets waiting for room.
+pub type Parked = Blocked<(Uuid, GetResponse, Sender<Result<SystemTime, Status>>)>;
+
 pub enum Req {
     Set(SetReq, Sender<Result<SystemTime, Status>>),
     Get(Uuid, Sender<Result<GetResponse, Status>>),
+    Wait(Uuid, oneshot::Sender<Result<GetResponse, Status>>),
+    Unwait(Uuid),
     Del(Uuid, Sender<Result<(), Status>>),
     Len(Sender<Result<u64, Status>>),
+    Stats(Sender<Result<ResStats, Status>>),
 }
 
 impl Req {
-    async fn handle_set(
-        d: &mut BTreeMap<Uuid, GetResponse>,
+    /// Hands the response to the oldest waiting get(if any); returns the response otherwise.
+    fn handoff(w: &mut Waiters, reply_id: Uuid, gr: GetResponse) -> Option<GetResponse> {
+        let Some(mut waiters) = w.remove(&reply_id) else {
+            return Some(gr);
+        };
+        let mut gr: GetResponse = gr;
+        while let Some(waiter) = waiters.pop_front() {
+            match waiter.send(Ok(gr)) {
+                Ok(_) => {
+                    // the others keep waiting(until their timeouts)
+                    if !waiters.is_empty() {
+                        w.insert(reply_id, waiters);
+                    }
+                    return None;
+                }
+                Err(r) => match r {
+                    Ok(back) => gr = back,
+                    Err(_) => return None,
+                },
+            }
+        }
+        Some(gr)
+    }
+
+    async fn handle_set<S: Store>(
+        d: &mut S,
+        w: &mut Waiters,
+        p: &mut Parked,
         req: SetReq,
         reply: Sender<Result<SystemTime, Status>>,
         max_size: usize,
+        full: FullPolicy,
     ) {
-        let sz: usize = d.len();
-        let too_many: bool = max_size < sz;
-        let available: bool = !too_many;
-        let r = available
-            .then_some(())
-            .ok_or_else(|| Status::unavailable(format!("too many requests. size: {sz}")))
-            .and_then(|_| {
-                let reply_id: Uuid = req.as_reply_id();
-                let dup_found: bool = d.contains_key(&reply_id);
-                let available: bool = !dup_found;
-                available.then_some(()).ok_or_else(|| {
-                    Status::already_exists(format!(
-                        "response for reply id({reply_id}) already exists"
-                    ))
-                })?;
-                let mut gr: GetResponse = req.into();
-                let set: SystemTime = SystemTime::now();
-                gr.set = Some(set.into());
-                d.insert(reply_id, gr);
-                Ok(set)
-            });
+        let reply_id: Uuid = req.as_reply_id();
+        let set: SystemTime = SystemTime::now();
+        let mut gr: GetResponse = req.into();
+        gr.set = Some(set.into());
+        let r = match Self::handoff(w, reply_id, gr) {
+            None => Ok(set),
+            Some(gr) => match Self::insert(d, reply_id, gr, max_size, full).await {
+                Ok(None) => Ok(set),
+                Ok(Some((wait, gr))) => {
+                    p.park(Instant::now() + wait, (reply_id, gr, reply));
+                    return;
+                }
+                Err(e) => Err(e),
+            },
+        };
         match reply.send(r).await {
             Ok(_) => {}
             Err(e) => log::warn!("Unable to send a set evt: {e}"),
         }
     }
 
-    async fn handle_get(
-        d: &mut BTreeMap<Uuid, GetResponse>,
+    fn too_many<S: Store>(d: &S, max_size: usize) -> Status {
+        let sz: u64 = d.count() as u64;
+        let oldest_age: Option<Duration> = Self::oldest_age(d, SystemTime::now());
+        Full::estimate(sz, max_size as u64, oldest_age).into()
+    }
+
+    /// Checks if the store holds `max_size` responses.
+    fn is_full<S: Store>(d: &S, max_size: usize) -> bool {
+        max_size <= d.count()
+    }
+
+    /// Inserts the response; returns the response to be parked if the buffer is full.
+    async fn insert<S: Store>(
+        d: &mut S,
+        reply_id: Uuid,
+        gr: GetResponse,
+        max_size: usize,
+        full: FullPolicy,
+    ) -> Result<Option<(Duration, GetResponse)>, Status> {
+        let dup_found: bool = d.contains(&reply_id);
+        if dup_found {
+            return Err(Status::already_exists(format!(
+                "response for reply id({reply_id}) already exists"
+            )));
+        }
+        match (Self::is_full(d, max_size), full) {
+            (false, _) => {}
+            (true, FullPolicy::Reject) => return Err(Self::too_many(d, max_size)),
+            (true, FullPolicy::Block(wait)) => return Ok(Some((wait, gr))),
+            (true, FullPolicy::DropOldest) => {
+                let oldest: Uuid = d.oldest().ok_or_else(|| Self::too_many(d, max_size))?;
+                d.remove(&oldest).await?;
+                log::warn!("dropped the oldest response. reply id: {oldest}");
+            }
+        }
+        d.insert(reply_id, gr).await?;
+        Ok(None)
+    }
+
+    /// Sets parked responses while the buffer has room.
+    async fn admit<S: Store>(d: &mut S, w: &mut Waiters, p: &mut Parked, max_size: usize) {
+        while !Self::is_full(d, max_size) {
+            let Some((reply_id, gr, reply)) = p.pop() else {
+                return;
+            };
+            if reply.is_closed() {
+                continue;
+            }
+            let set: Option<SystemTime> = gr.set.clone().and_then(|t| t.try_into().ok());
+            let r: Result<SystemTime, _> = match Self::handoff(w, reply_id, gr) {
+                None => Ok(()),
+                Some(gr) => Self::insert(d, reply_id, gr, max_size, FullPolicy::Reject)
+                    .await
+                    .map(|_| ()),
+            }
+            .map(|_| set.unwrap_or_else(SystemTime::now));
+            match reply.send(r).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a set evt: {e}"),
+            }
+        }
+    }
+
+    /// Rejects parked sets which waited too long.
+    async fn handle_parked<S: Store>(d: &S, p: &mut Parked, now: Instant, max_size: usize) {
+        for (_, _, reply) in p.expired(now) {
+            match reply.send(Err(Self::too_many(d, max_size))).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a set evt: {e}"),
+            }
+        }
+    }
+
+    async fn handle_get<S: Store>(
+        d: &mut S,
         reply_id: Uuid,
         reply: Sender<Result<GetResponse, Status>>,
     ) {
-        let r = d.remove(&reply_id).ok_or_else(|| {
-            Status::not_found(format!("No reply found(for now). reply id: {reply_id}"))
+        let r = d.remove(&reply_id).await.and_then(|o| {
+            o.ok_or_else(|| {
+                Status::not_found(format!("No reply found(for now). reply id: {reply_id}"))
+            })
         });
         match reply.send(r).await {
             Ok(_) => {}
@@ -79,15 +195,44 @@ impl Req {
         }
     }
 
-    async fn handle_del(
-        d: &mut BTreeMap<Uuid, GetResponse>,
+    async fn handle_wait<S: Store>(
+        d: &mut S,
+        w: &mut Waiters,
         reply_id: Uuid,
-        reply: Sender<Result<(), Status>>,
+        reply: oneshot::Sender<Result<GetResponse, Status>>,
     ) {
-        let r = d.remove(&reply_id).map(|_| ()).ok_or_else(|| {
-            Status::not_found(format!(
-                "No reply found(may be consumed). reply id: {reply_id}"
-            ))
+        let r: Result<_, _> = match d.remove(&reply_id).await {
+            Err(e) => Err(e),
+            Ok(Some(gr)) => Ok(gr),
+            Ok(None) => {
+                w.entry(reply_id).or_default().push_back(reply);
+                return;
+            }
+        };
+        match reply.send(r) {
+            Ok(_) => {}
+            Err(_) => log::warn!("Unable to send a wait evt. reply id: {reply_id}"),
+        }
+    }
+
+    /// Removes the waiters which gave up.
+    fn handle_unwait(w: &mut Waiters, reply_id: Uuid) {
+        let Some(waiters) = w.get_mut(&reply_id) else {
+            return;
+        };
+        waiters.retain(|o| !o.is_closed());
+        if waiters.is_empty() {
+            w.remove(&reply_id);
+        }
+    }
+
+    async fn handle_del<S: Store>(d: &mut S, reply_id: Uuid, reply: Sender<Result<(), Status>>) {
+        let r = d.remove(&reply_id).await.and_then(|o| {
+            o.map(|_| ()).ok_or_else(|| {
+                Status::not_found(format!(
+                    "No reply found(may be consumed). reply id: {reply_id}"
+                ))
+            })
         });
         match reply.send(r).await {
             Ok(_) => {}
@@ -95,13 +240,32 @@ impl Req {
         }
     }
 
-    async fn handle_len(d: &BTreeMap<Uuid, GetResponse>, reply: Sender<Result<u64, Status>>) {
-        let sz: usize = d.len();
+    async fn handle_len<S: Store>(d: &S, reply: Sender<Result<u64, Status>>) {
+        let sz: usize = d.count();
         match reply.send(Ok(sz as u64)).await {
             Ok(_) => {}
             Err(e) => log::warn!("Unable to send a count evt: {e}"),
         }
     }
+
+    fn oldest_age<S: Store>(d: &S, now: SystemTime) -> Option<Duration> {
+        let oldest: SystemTime = d.oldest_set()?;
+        Some(now.duration_since(oldest).unwrap_or_default())
+    }
+
+    async fn handle_stats<S: Store>(
+        d: &S,
+        reply: Sender<Result<ResStats, Status>>,
+        max_size: usize,
+    ) {
+        let pending: u64 = d.count() as u64;
+        let oldest_age: Option<Duration> = Self::oldest_age(d, SystemTime::now());
+        let stats = ResStats::new(max_size as u64, pending, oldest_age);
+        match reply.send(Ok(stats)).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a stats evt: {e}"),
+        }
+    }
 }
 
 pub struct BufSvcSt {
@@ -151,6 +315,70 @@ impl BufSvcSt {
         res.map(|_| SystemTime::now())
     }
 
+    pub async fn count(&self) -> Result<u64, Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req = Req::Len(tx);
+        self.sender
+            .send(req)
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a len request: {e}")))?;
+        let res: Result<_, _> = rx
+            .recv()
+            .await
+            .ok_or_else(|| Status::internal("no response got"))?;
+        res
+    }
+
+    pub async fn stats(&self) -> Result<ResStats, Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req = Req::Stats(tx);
+        self.sender
+            .send(req)
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a stats request: {e}")))?;
+        let res: Result<_, _> = rx
+            .recv()
+            .await
+            .ok_or_else(|| Status::internal("no response got"))?;
+        res
+    }
+
+    /// Waits for the response until it is set or the timeout elapses.
+    ///
+    /// A response is taken by a single get(the oldest waiting one); other gets waiting for
+    /// the same reply id keep waiting until their timeouts.
+    pub async fn wait(
+        sender: &Sender<Req>,
+        reply_id: Uuid,
+        timeout: Duration,
+    ) -> Result<GetResponse, Status> {
+        let (tx, mut rx) = oneshot::channel();
+        let req = Req::Wait(reply_id, tx);
+        sender
+            .send(req)
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a wait request: {e}")))?;
+        match tokio::time::timeout(timeout, &mut rx).await {
+            Ok(Ok(r)) => r,
+            Ok(Err(_)) => Err(Status::internal("no response got")),
+            Err(_) => {
+                // the response may have been handed off just before the close
+                rx.close();
+                if let Ok(r) = rx.try_recv() {
+                    return r;
+                }
+                match sender.send(Req::Unwait(reply_id)).await {
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Unable to send an unwait request: {e}"),
+                }
+                Err(Status::deadline_exceeded(format!(
+                    "timeout. elapsed={timeout:#?}, reply id: {reply_id}"
+                )))
+            }
+        }
+    }
+
+    /// Gets the response; waits until it is set(or the retry timeout elapses).
     pub async fn get(
         &self,
         req: GetReq,
@@ -158,50 +386,13 @@ impl BufSvcSt {
         let sender = self.sender.clone();
         let reply_id: Uuid = req.as_reply_id();
         let retry: &Retry = req.as_retry();
-        let retry_max: u64 = retry.as_retry_max();
-        let invl: Duration = retry.as_interval();
         let timeout: Duration = retry.as_timeout();
         let (tx, rx) = tokio::sync::mpsc::channel(1);
         tokio::spawn(async move {
-            let mut interval: Interval = tokio::time::interval(invl);
-            let started: Instant = Instant::now();
-            for i in 0..retry_max {
-                interval.tick().await;
-                let r = Instant::now()
-                    .checked_duration_since(started)
-                    .ok_or_else(|| Status::internal("CLOCK BROKEN"))
-                    .and_then(|elapsed: Duration| {
-                        let too_slow: bool = timeout < elapsed;
-                        let fast: bool = !too_slow;
-                        fast.then_some(()).ok_or_else(|| {
-                            Status::deadline_exceeded(format!(
-                                "timeout. elapsed={elapsed:#?}, try count={i}"
-                            ))
-                        })
-                    });
-                let r = match r {
-                    Ok(_) => Self::get1(&sender, reply_id).await,
-                    Err(e) => Err(e),
-                };
-                match r {
-                    Ok(r) => {
-                        match tx.send(Ok(r)).await {
-                            Ok(_) => {}
-                            Err(e) => log::warn!("Unable to send a response: {e}"),
-                        };
-                        return;
-                    }
-                    Err(e) => match e.code() {
-                        Code::NotFound => continue,
-                        _ => {
-                            match tx.send(Err(e)).await {
-                                Ok(_) => {}
-                                Err(e) => log::warn!("Unable to send a reply: {e}"),
-                            }
-                            return;
-                        }
-                    },
-                }
+            let r: Result<_, _> = Self::wait(&sender, reply_id, timeout).await;
+            match tx.send(r).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a response: {e}"),
             }
         });
         Ok(ReceiverStream::new(rx))
@@ -213,8 +404,9 @@ impl ResBufferService for BufSvcSt {
     type GetStream = ReceiverStream<Result<GetResponse, Status>>;
 
     async fn get(&self, req: Request<GetRequest>) -> Result<Response<Self::GetStream>, Status> {
+        let deadline: Option<Duration> = deadline::from_metadata(req.metadata());
         let gr: GetRequest = req.into_inner();
-        let checked: GetReq = gr.try_into()?;
+        let checked: GetReq = GetReq::try_from(gr)?.within(deadline);
         let reply = self.get(checked).await?;
         Ok(Response::new(reply))
     }
@@ -241,29 +433,91 @@ impl ResBufferService for BufSvcSt {
     }
 
     async fn len(&self, _req: Request<LenRequest>) -> Result<Response<LenResponse>, Status> {
-        todo!()
+        let length: u64 = self.count().await?;
+        let reply = LenResponse { length };
+        Ok(Response::new(reply))
+    }
+
+    async fn stats(&self, _req: Request<StatsRequest>) -> Result<Response<StatsResponse>, Status> {
+        let stats: ResStats = self.stats().await?;
+        Ok(Response::new(stats.into()))
     }
 }
 
-async fn buf_svc_st_new(max_size: usize) -> BufSvcSt {
-    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+/// Creates a buffer keeping responses in the store.
+pub(crate) async fn buf_svc_st_with<S>(cfg: &ActorConfig, store: S, max_size: usize) -> BufSvcSt
+where
+    S: Store,
+{
+    let (tx, mut rx) = cfg.channel();
+    let full: FullPolicy = cfg.as_full_policy();
     tokio::spawn(async move {
-        let mut bm: BTreeMap<Uuid, GetResponse> = BTreeMap::new();
+        let mut bm: S = store;
+        let mut waiters: Waiters = BTreeMap::new();
+        let mut parked: Parked = Blocked::default();
         loop {
-            match rx.recv().await {
-                None => return,
-                Some(req) => match req {
-                    Req::Set(q, reply) => Req::handle_set(&mut bm, q, reply, max_size).await,
-                    Req::Get(reply_id, reply) => Req::handle_get(&mut bm, reply_id, reply).await,
-                    Req::Del(reply_id, reply) => Req::handle_del(&mut bm, reply_id, reply).await,
-                    Req::Len(reply) => Req::handle_len(&bm, reply).await,
+            let next: Option<Instant> = parked.next_deadline();
+            let expire = async move {
+                match next {
+                    None => std::future::pending().await,
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                }
+            };
+            // parked sets may fit once another shard removes a response
+            let freed: Option<Arc<Notify>> = bm.freed().filter(|_| !parked.is_empty());
+            let room = async move {
+                match freed {
+                    None => std::future::pending().await,
+                    Some(n) => n.notified().await,
+                }
+            };
+            tokio::select! {
+                o = rx.recv() => match o {
+                    None => return,
+                    Some(req) => match req {
+                        Req::Set(q, reply) => {
+                            Req::handle_set(
+                                &mut bm,
+                                &mut waiters,
+                                &mut parked,
+                                q,
+                                reply,
+                                max_size,
+                                full,
+                            )
+                            .await
+                        }
+                        Req::Get(reply_id, reply) => Req::handle_get(&mut bm, reply_id, reply).await,
+                        Req::Wait(reply_id, reply) => {
+                            Req::handle_wait(&mut bm, &mut waiters, reply_id, reply).await
+                        }
+                        Req::Unwait(reply_id) => Req::handle_unwait(&mut waiters, reply_id),
+                        Req::Del(reply_id, reply) => Req::handle_del(&mut bm, reply_id, reply).await,
+                        Req::Len(reply) => Req::handle_len(&bm, reply).await,
+                        Req::Stats(reply) => Req::handle_stats(&bm, reply, max_size).await,
+                    },
                 },
+                _ = expire => Req::handle_parked(&bm, &mut parked, Instant::now(), max_size).await,
+                _ = room => {}
             }
+            Req::admit(&mut bm, &mut waiters, &mut parked, max_size).await;
         }
     });
     BufSvcSt { sender: tx }
 }
 
+pub(crate) async fn buf_svc_st_new(cfg: &ActorConfig, max_size: usize) -> BufSvcSt {
+    buf_svc_st_with(cfg, MemStore::default(), max_size).await
+}
+
 pub async fn res_buffer_service_new(max_size: usize) -> impl ResBufferService {
-    buf_svc_st_new(max_size).await
+    res_buffer_service_with_config(&ActorConfig::default(), max_size).await
+}
+
+/// Creates a buffer using the config.
+pub async fn res_buffer_service_with_config(
+    cfg: &ActorConfig,
+    max_size: usize,
+) -> impl ResBufferService {
+    buf_svc_st_new(cfg, max_size).await
 }
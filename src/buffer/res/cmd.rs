@@ -1,3 +1,4 @@
 pub mod del;
 pub mod get;
 pub mod set;
+pub mod stats;
@@ -1,3 +1,5 @@
+use core::time::Duration;
+
 use tonic::Status;
 
 use crate::retry::Retry;
@@ -24,6 +26,12 @@ impl GetReq {
     pub fn as_retry(&self) -> &Retry {
         &self.retry
     }
+
+    /// Shortens the timeout of the retry to the request deadline(if any).
+    pub fn within(mut self, deadline: Option<Duration>) -> Self {
+        self.retry = self.retry.within(deadline);
+        self
+    }
 }
 
 impl TryFrom<GetRequest> for GetReq {
@@ -16,9 +16,10 @@ pub struct SetReq {
     request_id: Uuid,
     reply_id: Uuid,
     response: ConvertResponse,
-    received: Timestamp,
-    saved: Timestamp,
+    received: Option<Timestamp>,
+    saved: Option<Timestamp>,
     converted: Timestamp,
+    loaded: Option<Timestamp>,
 }
 
 impl SetReq {
@@ -30,15 +31,18 @@ impl SetReq {
         self.reply_id
     }
 
-    pub fn as_received(&self) -> &Timestamp {
-        &self.received
+    pub fn as_received(&self) -> Option<&Timestamp> {
+        self.received.as_ref()
     }
-    pub fn as_saved(&self) -> &Timestamp {
-        &self.saved
+    pub fn as_saved(&self) -> Option<&Timestamp> {
+        self.saved.as_ref()
     }
     pub fn as_converted(&self) -> &Timestamp {
         &self.converted
     }
+    pub fn as_loaded(&self) -> Option<&Timestamp> {
+        self.loaded.as_ref()
+    }
 
     pub fn into_response(self) -> ConvertResponse {
         self.response
@@ -59,14 +63,6 @@ impl TryFrom<SetRequest> for SetReq {
         let response: ConvertResponse = g.res.ok_or_else(|| {
             Status::invalid_argument(format!("response missing. request id: {request_id}"))
         })?;
-        let received: Timestamp = g.received.ok_or_else(|| {
-            Status::invalid_argument(format!("received missing. request id: {request_id}"))
-        })?;
-
-        let saved: Timestamp = g.saved.ok_or_else(|| {
-            Status::invalid_argument(format!("saved missing. request id: {request_id}"))
-        })?;
-
         let converted: Timestamp = g.converted.ok_or_else(|| {
             Status::invalid_argument(format!("converted missing. request id: {request_id}"))
         })?;
@@ -75,9 +71,10 @@ impl TryFrom<SetRequest> for SetReq {
             request_id,
             reply_id,
             response,
-            received,
-            saved,
+            received: g.received,
+            saved: g.saved,
             converted,
+            loaded: g.loaded,
         })
     }
 }
@@ -86,10 +83,11 @@ impl From<SetReq> for GetResponse {
     fn from(d: SetReq) -> Self {
         Self {
             res: Some(d.response),
-            received: Some(d.received),
-            saved: Some(d.saved),
+            received: d.received,
+            saved: d.saved,
             converted: Some(d.converted),
             set: Some(SystemTime::now().into()),
+            loaded: d.loaded,
         }
     }
 }
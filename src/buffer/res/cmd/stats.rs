@@ -0,0 +1,43 @@
+use core::time::Duration;
+
+use crate::retry::budget;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::StatsResponse;
+
+pub struct ResStats {
+    capacity: u64,
+    pending: u64,
+    oldest_age: Option<Duration>,
+}
+
+impl ResStats {
+    pub fn new(capacity: u64, pending: u64, oldest_age: Option<Duration>) -> Self {
+        Self {
+            capacity,
+            pending,
+            oldest_age,
+        }
+    }
+
+    pub fn as_capacity(&self) -> u64 {
+        self.capacity
+    }
+    pub fn as_pending(&self) -> u64 {
+        self.pending
+    }
+    pub fn as_oldest_age(&self) -> Option<Duration> {
+        self.oldest_age
+    }
+}
+
+impl From<ResStats> for StatsResponse {
+    fn from(d: ResStats) -> Self {
+        Self {
+            capacity: d.capacity,
+            pending: d.pending,
+            oldest_age: d.oldest_age.and_then(|a| a.try_into().ok()),
+            retries: Some(budget::shared().snapshot().into()),
+        }
+    }
+}
@@ -3,3 +3,4 @@ pub mod svc;
 pub mod auto;
 
 pub mod count;
+pub mod ttl;
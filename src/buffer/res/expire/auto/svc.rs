@@ -1,5 +1,13 @@
-use tokio::sync::mpsc::Sender;
-use tokio_stream::wrappers::ReceiverStream;
+use core::time::Duration;
+use std::sync::Arc;
+
+use futures::StreamExt;
+
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Interval;
+
+use tonic::{Code, Request, Response, Status};
 
 use crate::uuid::Uuid;
 
@@ -8,27 +16,96 @@ use crate::buffer::res::expire::svc::ExpireService;
 use crate::rpc::perf::helper;
 use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
 
-use core::time::Duration;
-use std::time::{Instant, SystemTime};
+use helper::proto::buffer::v1::res_buf::GetRequest;
+use helper::proto::buffer::v1::res_buf::{DelRequest, DelResponse};
+use helper::proto::buffer::v1::res_buf::{LenRequest, LenResponse};
+use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
 
-use tokio::time::Interval;
+pub struct AutoExpireSvc<B, E> {
+    buf: Arc<B>,
+    expire: Arc<E>,
+}
 
-use tonic::{Code, Request, Response, Status};
+impl<B, E> AutoExpireSvc<B, E>
+where
+    B: Send + Sync + 'static + ResBufferService,
+    E: Send + Sync + 'static + ExpireService,
+{
+    async fn remove(buf: &B, expire: &E, key: Uuid) -> Result<(), Status> {
+        expire.forget_key(key).await?;
+        let req = DelRequest {
+            request_id: Some(Uuid::generate().into()),
+            reply_id: Some(key.into()),
+        };
+        match buf.del(Request::new(req)).await {
+            Ok(_) => Ok(()),
+            Err(e) => match e.code() {
+                Code::NotFound => Ok(()),
+                _ => Err(e),
+            },
+        }
+    }
 
-use crate::retry::Retry;
+    /// Ticks the expirer and removes expired responses; returns the number of expired keys found.
+    pub async fn sweep(buf: &B, expire: &E) -> Result<u64, Status> {
+        expire.tick().await?;
+        let keys: E::ExpiredKeysStream = expire.expired_keys().await?;
+        let keys: Vec<Result<Uuid, Status>> = keys.collect().await;
+        let mut cnt: u64 = 0;
+        for rk in keys {
+            let key: Uuid = rk?;
+            match Self::remove(buf, expire, key).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to remove an expired response({key}): {e}"),
+            }
+            cnt += 1;
+        }
+        Ok(cnt)
+    }
+}
 
-use crate::buffer::res::cmd::del::DelReq;
-use crate::buffer::res::cmd::get::GetReq;
-use crate::buffer::res::cmd::set::SetReq;
+/// Handle of the background task which removes expired responses.
+pub struct Sweeper {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
 
-use helper::proto::buffer::v1::res_buf::{DelRequest, DelResponse};
-use helper::proto::buffer::v1::res_buf::{GetRequest, GetResponse};
-use helper::proto::buffer::v1::res_buf::{LenRequest, LenResponse};
-use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+impl Sweeper {
+    /// Stops the sweeper and waits for the task to finish.
+    pub async fn shutdown(self) -> Result<(), Status> {
+        match self.stop.send(()) {
+            Ok(_) => {}
+            Err(_) => log::info!("sweeper already stopped"),
+        }
+        self.handle
+            .await
+            .map_err(|e| Status::internal(format!("Unable to stop the sweeper: {e}")))
+    }
+}
 
-pub struct AutoExpireSvc<B, E> {
-    buf: B,
-    expire: E,
+fn sweeper_new<B, E>(buf: Arc<B>, expire: Arc<E>, interval: Duration) -> Sweeper
+where
+    B: Send + Sync + 'static + ResBufferService,
+    E: Send + Sync + 'static + ExpireService,
+{
+    let (stop, mut stopped) = oneshot::channel();
+    let handle: JoinHandle<()> = tokio::spawn(async move {
+        let mut invl: Interval = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = &mut stopped => return,
+                _ = invl.tick() => {
+                    match AutoExpireSvc::sweep(buf.as_ref(), expire.as_ref()).await {
+                        Ok(0) => {}
+                        Ok(cnt) => log::debug!("expired responses: {cnt}"),
+                        Err(e) => log::warn!("Unable to sweep: {e}"),
+                    }
+                }
+            }
+        }
+    });
+    Sweeper { stop, handle }
 }
 
 #[tonic::async_trait]
@@ -51,7 +128,14 @@ where
             .map(|u| u.into())
             .ok_or_else(|| Status::invalid_argument("reply id missing"))?;
         self.expire.register_key(id).await?;
-        self.buf.set(req).await
+        let r: Result<_, _> = self.buf.set(req).await;
+        if r.is_err() {
+            // the key of a response not kept must not expire
+            if let Err(e) = self.expire.forget_key(id).await {
+                log::warn!("Unable to forget a key({id}): {e}");
+            }
+        }
+        r
     }
 
     async fn del(&self, req: Request<DelRequest>) -> Result<Response<DelResponse>, Status> {
@@ -61,11 +145,38 @@ where
             .as_ref()
             .map(|u| u.into())
             .ok_or_else(|| Status::invalid_argument("reply id missing"))?;
-        self.expire.forget_key(id).await?;
+        match self.expire.forget_key(id).await {
+            Ok(_) => {}
+            Err(e) => match e.code() {
+                Code::NotFound => {}
+                _ => return Err(e),
+            },
+        }
         self.buf.del(req).await
     }
 
     async fn len(&self, req: Request<LenRequest>) -> Result<Response<LenResponse>, Status> {
         self.buf.len(req).await
     }
+
+    async fn stats(&self, req: Request<StatsRequest>) -> Result<Response<StatsResponse>, Status> {
+        self.buf.stats(req).await
+    }
+}
+
+/// Creates a [`ResBufferService`] which registers keys of responses to the [`ExpireService`]
+/// and a [`Sweeper`] which removes expired responses every interval.
+pub async fn auto_expire_svc_new<B, E>(
+    buf: B,
+    expire: E,
+    interval: Duration,
+) -> (AutoExpireSvc<B, E>, Sweeper)
+where
+    B: Send + Sync + 'static + ResBufferService,
+    E: Send + Sync + 'static + ExpireService,
+{
+    let buf: Arc<B> = Arc::new(buf);
+    let expire: Arc<E> = Arc::new(expire);
+    let sweeper: Sweeper = sweeper_new(buf.clone(), expire.clone(), interval);
+    (AutoExpireSvc { buf, expire }, sweeper)
 }
@@ -7,6 +7,7 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use tonic::{Code, Status};
 
+use crate::actor::ActorConfig;
 use crate::uuid::Uuid;
 
 use crate::buffer::res::expire::svc::ExpireService;
@@ -25,7 +26,7 @@ impl Container {
     }
 
     fn is_expired(cnt: u64, max_cnt: u64) -> bool {
-        max_cnt < cnt
+        max_cnt <= cnt
     }
 
     fn next_expired_key(&self, prev: Uuid, max_cnt: u64) -> Option<Uuid> {
@@ -52,11 +53,38 @@ impl Container {
             }
         }
     }
+
+    /// Bumps counters of all keys.
+    pub fn tick(&mut self) {
+        for cnt in self.m.values_mut() {
+            *cnt = cnt.saturating_add(1);
+        }
+    }
+
+    /// Bumps the counter of the key.
+    pub fn touch(&mut self, key: Uuid) -> Result<(), Status> {
+        let cnt: &mut u64 = self
+            .m
+            .get_mut(&key)
+            .ok_or_else(|| Status::not_found(format!("no such key: {key}")))?;
+        *cnt = cnt.saturating_add(1);
+        Ok(())
+    }
+
+    pub fn forget(&mut self, key: Uuid) -> Result<(), Status> {
+        self.m
+            .remove(&key)
+            .map(|_| ())
+            .ok_or_else(|| Status::not_found(format!("no such key: {key}")))
+    }
 }
 
 pub enum Req {
     ExpiredKey(Option<Uuid>, Sender<Result<Uuid, Status>>),
     Register(Uuid, Sender<Result<(), Status>>),
+    Forget(Uuid, Sender<Result<(), Status>>),
+    Touch(Uuid, Sender<Result<(), Status>>),
+    Tick(Sender<Result<(), Status>>),
 }
 
 impl Req {
@@ -84,6 +112,34 @@ impl Req {
             Err(e) => log::warn!("Unable to send a register evt: {e}"),
         }
     }
+
+    pub async fn handle_forget_key(
+        c: &mut Container,
+        key: Uuid,
+        reply: Sender<Result<(), Status>>,
+    ) {
+        let r: Result<_, _> = c.forget(key);
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a forget evt: {e}"),
+        }
+    }
+
+    pub async fn handle_touch_key(c: &mut Container, key: Uuid, reply: Sender<Result<(), Status>>) {
+        let r: Result<_, _> = c.touch(key);
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a touch evt: {e}"),
+        }
+    }
+
+    pub async fn handle_tick(c: &mut Container, reply: Sender<Result<(), Status>>) {
+        c.tick();
+        match reply.send(Ok(())).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a tick evt: {e}"),
+        }
+    }
 }
 
 pub struct Svc {
@@ -112,6 +168,39 @@ impl Svc {
             .await
             .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
     }
+
+    pub async fn forget_key(s: &Sender<Req>, key: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::Forget(key, tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
+
+    pub async fn touch_key(s: &Sender<Req>, key: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::Touch(key, tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
+
+    pub async fn tick(s: &Sender<Req>) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::Tick(tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
 }
 
 #[tonic::async_trait]
@@ -153,10 +242,22 @@ impl ExpireService for Svc {
     async fn register_key(&self, key: Uuid) -> Result<(), Status> {
         Self::register_key(&self.sender, key).await
     }
+
+    async fn tick(&self) -> Result<(), Status> {
+        Self::tick(&self.sender).await
+    }
+
+    async fn touch_key(&self, key: Uuid) -> Result<(), Status> {
+        Self::touch_key(&self.sender, key).await
+    }
+
+    async fn forget_key(&self, key: Uuid) -> Result<(), Status> {
+        Self::forget_key(&self.sender, key).await
+    }
 }
 
-async fn svc_new(max_cnt: u64, mut c: Container) -> Svc {
-    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+async fn svc_new(cfg: &ActorConfig, max_cnt: u64, mut c: Container) -> Svc {
+    let (tx, mut rx) = cfg.channel();
     tokio::spawn(async move {
         loop {
             match rx.recv().await {
@@ -167,12 +268,21 @@ async fn svc_new(max_cnt: u64, mut c: Container) -> Svc {
                 Some(Req::Register(key, reply)) => {
                     Req::handle_register_key(&mut c, key, reply).await
                 }
+                Some(Req::Forget(key, reply)) => Req::handle_forget_key(&mut c, key, reply).await,
+                Some(Req::Touch(key, reply)) => Req::handle_touch_key(&mut c, key, reply).await,
+                Some(Req::Tick(reply)) => Req::handle_tick(&mut c, reply).await,
             }
         }
     });
     Svc { sender: tx }
 }
 
+/// Creates an [`ExpireService`] which expires keys ticked/touched `max_cnt` times.
 pub async fn expire_service_new(max_cnt: u64) -> impl ExpireService {
-    svc_new(max_cnt, Container::default()).await
+    expire_service_with_config(&ActorConfig::default(), max_cnt).await
+}
+
+/// Creates an [`ExpireService`] which expires keys ticked/touched `max_cnt` times using the config.
+pub async fn expire_service_with_config(cfg: &ActorConfig, max_cnt: u64) -> impl ExpireService {
+    svc_new(cfg, max_cnt, Container::default()).await
 }
@@ -11,6 +11,18 @@ pub trait ExpireService {
 
     async fn register_key(&self, key: Uuid) -> Result<(), Status>;
 
+    /// tick advances the internal clock of this service(e.g, once per sweep cycle).
+    /// Services which do not need explicit ticks can use this provided method.
+    async fn tick(&self) -> Result<(), Status> {
+        Ok(())
+    }
+
+    /// touch_key bumps the expiration state of the key(e.g, on a failed get).
+    /// Services which do not need touches can use this provided method.
+    async fn touch_key(&self, _key: Uuid) -> Result<(), Status> {
+        Ok(())
+    }
+
     /// forget_key forgets the key registered by register_key.
     /// This "provided method" can not be used(actual implementation required)
     async fn forget_key(&self, _key: Uuid) -> Result<(), Status> {
@@ -0,0 +1,229 @@
+use core::ops::Bound::{Excluded, Unbounded};
+use core::time::Duration;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+use tokio::sync::mpsc::Sender;
+use tokio_stream::wrappers::ReceiverStream;
+
+use tonic::{Code, Status};
+
+use crate::actor::ActorConfig;
+use crate::uuid::Uuid;
+
+use crate::buffer::res::expire::svc::ExpireService;
+
+/// Keys with deadlines; expired keys are found in deadline order.
+#[derive(Default)]
+pub struct Container {
+    deadlines: BTreeMap<Uuid, Instant>,
+    ordered: BTreeSet<(Instant, Uuid)>,
+}
+
+impl Container {
+    pub fn expired_key(
+        &self,
+        prev: Option<(Instant, Uuid)>,
+        now: Instant,
+    ) -> Result<(Instant, Uuid), Status> {
+        let o: Option<&(Instant, Uuid)> = match prev {
+            None => self.ordered.iter().next(),
+            Some(p) => self.ordered.range((Excluded(p), Unbounded)).next(),
+        };
+        o.filter(|t| t.0 <= now)
+            .copied()
+            .ok_or_else(|| Status::not_found("no expired keys found"))
+    }
+
+    pub fn register(&mut self, key: Uuid, deadline: Instant) -> Result<(), Status> {
+        match self.deadlines.contains_key(&key) {
+            true => Err(Status::already_exists(format!("dup found. key: {key}"))),
+            false => {
+                self.deadlines.insert(key, deadline);
+                self.ordered.insert((deadline, key));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn forget(&mut self, key: Uuid) -> Result<(), Status> {
+        let deadline: Instant = self
+            .deadlines
+            .remove(&key)
+            .ok_or_else(|| Status::not_found(format!("no such key: {key}")))?;
+        self.ordered.remove(&(deadline, key));
+        Ok(())
+    }
+}
+
+pub enum Req {
+    ExpiredKey(
+        Option<(Instant, Uuid)>,
+        Instant,
+        Sender<Result<(Instant, Uuid), Status>>,
+    ),
+    Register(Uuid, Sender<Result<(), Status>>),
+    Forget(Uuid, Sender<Result<(), Status>>),
+}
+
+impl Req {
+    pub async fn handle_expired_key(
+        c: &Container,
+        prev: Option<(Instant, Uuid)>,
+        now: Instant,
+        reply: Sender<Result<(Instant, Uuid), Status>>,
+    ) {
+        let r: Result<_, _> = c.expired_key(prev, now);
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send an expired key: {e}"),
+        }
+    }
+
+    pub async fn handle_register_key(
+        c: &mut Container,
+        key: Uuid,
+        ttl: Duration,
+        reply: Sender<Result<(), Status>>,
+    ) {
+        let r: Result<_, _> = Instant::now()
+            .checked_add(ttl)
+            .ok_or_else(|| Status::invalid_argument(format!("ttl too long: {ttl:#?}")))
+            .and_then(|deadline: Instant| c.register(key, deadline));
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a register evt: {e}"),
+        }
+    }
+
+    pub async fn handle_forget_key(
+        c: &mut Container,
+        key: Uuid,
+        reply: Sender<Result<(), Status>>,
+    ) {
+        let r: Result<_, _> = c.forget(key);
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a forget evt: {e}"),
+        }
+    }
+}
+
+pub struct Svc {
+    sender: Sender<Req>,
+}
+
+impl Svc {
+    pub async fn get_expired_key(
+        s: &Sender<Req>,
+        prev: Option<(Instant, Uuid)>,
+        now: Instant,
+    ) -> Result<(Instant, Uuid), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::ExpiredKey(prev, now, tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
+
+    pub async fn register_key(s: &Sender<Req>, key: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::Register(key, tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
+
+    pub async fn forget_key(s: &Sender<Req>, key: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let req: Req = Req::Forget(key, tx);
+        s.send(req)
+            .await
+            .map_err(|e| Status::internal(format!("UNABLE TO SEND A REQUEST: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("NO RESPONSE GOT"))?
+    }
+}
+
+#[tonic::async_trait]
+impl ExpireService for Svc {
+    type ExpiredKeysStream = ReceiverStream<Result<Uuid, Status>>;
+
+    async fn expired_keys(&self) -> Result<Self::ExpiredKeysStream, Status> {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let s: Sender<Req> = self.sender.clone();
+        let now: Instant = Instant::now();
+        tokio::spawn(async move {
+            let mut prev: Option<(Instant, Uuid)> = None;
+            loop {
+                match Self::get_expired_key(&s, prev, now).await {
+                    Err(e) => match e.code() {
+                        Code::NotFound => return,
+                        _ => match tx.send(Err(e)).await {
+                            Ok(_) => return,
+                            Err(e) => {
+                                log::warn!("UNABLE TO SEND AN ERROR: {e}");
+                                return;
+                            }
+                        },
+                    },
+                    Ok(found) => match tx.send(Ok(found.1)).await {
+                        Err(e) => {
+                            log::warn!("Unable to send an expired key: {e}");
+                            return;
+                        }
+                        Ok(_) => {
+                            prev = Some(found);
+                        }
+                    },
+                }
+            }
+        });
+        Ok(ReceiverStream::new(rx))
+    }
+
+    async fn register_key(&self, key: Uuid) -> Result<(), Status> {
+        Self::register_key(&self.sender, key).await
+    }
+
+    async fn forget_key(&self, key: Uuid) -> Result<(), Status> {
+        Self::forget_key(&self.sender, key).await
+    }
+}
+
+async fn svc_new(cfg: &ActorConfig, ttl: Duration, mut c: Container) -> Svc {
+    let (tx, mut rx) = cfg.channel();
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                None => return,
+                Some(Req::ExpiredKey(prev, now, reply)) => {
+                    Req::handle_expired_key(&c, prev, now, reply).await
+                }
+                Some(Req::Register(key, reply)) => {
+                    Req::handle_register_key(&mut c, key, ttl, reply).await
+                }
+                Some(Req::Forget(key, reply)) => Req::handle_forget_key(&mut c, key, reply).await,
+            }
+        }
+    });
+    Svc { sender: tx }
+}
+
+/// Creates an [`ExpireService`] which expires keys `ttl` after registration.
+pub async fn expire_service_new(ttl: Duration) -> impl ExpireService {
+    expire_service_with_config(&ActorConfig::default(), ttl).await
+}
+
+/// Creates an [`ExpireService`] which expires keys `ttl` after registration using the config.
+pub async fn expire_service_with_config(cfg: &ActorConfig, ttl: Duration) -> impl ExpireService {
+    svc_new(cfg, ttl, Container::default()).await
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use prost::Message;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::oneshot;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::buffer::res::store::{SetIndex, Store};
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+use helper::proto::buffer::v1::res_log::record::Record as Kind;
+use helper::proto::buffer::v1::res_log::{Entry, Record};
+
+fn io2status(e: std::io::Error, msg: &str) -> Status {
+    Status::internal(format!("{msg}: {e}"))
+}
+
+/// Where a response is in the log.
+#[derive(Clone, Copy)]
+struct Loc {
+    offset: u64,
+    len: usize,
+    set: Option<SystemTime>,
+}
+
+/// Responses kept in an append only log; the index in memory knows where they are.
+///
+/// Changes survive restarts of the process; they are fsynced only when compacted.
+/// The log does blocking io; [`FileStore`] runs it on a dedicated thread.
+pub struct Log {
+    path: PathBuf,
+    writer: File,
+    reader: File,
+    index: BTreeMap<Uuid, Loc>,
+    end: u64,
+    garbage: u64,
+    compact_threshold: u64,
+}
+
+impl Log {
+    fn encode(r: &Record) -> Result<Vec<u8>, Status> {
+        let mut buf: Vec<u8> = Vec::with_capacity(r.encoded_len() + 10);
+        r.encode_length_delimited(&mut buf)
+            .map_err(|e| Status::internal(format!("Unable to encode a record: {e}")))?;
+        Ok(buf)
+    }
+
+    fn scan(path: &Path) -> Result<BTreeMap<Uuid, Loc>, Status> {
+        let mut index: BTreeMap<Uuid, Loc> = BTreeMap::new();
+        let bytes: Vec<u8> = match fs::read(path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(index),
+            Err(e) => return Err(io2status(e, "Unable to read the log")),
+        };
+        let mut buf: &[u8] = &bytes;
+        while !buf.is_empty() {
+            let offset: u64 = (bytes.len() - buf.len()) as u64;
+            let r: Record = match Record::decode_length_delimited(&mut buf) {
+                Ok(r) => r,
+                Err(e) => {
+                    // a torn tail of an interrupted append
+                    log::warn!(
+                        "ignored the broken tail of the log({} bytes): {e}",
+                        buf.len()
+                    );
+                    break;
+                }
+            };
+            let len: usize = (bytes.len() - buf.len()) - offset as usize;
+            match r.record {
+                None => {}
+                Some(Kind::Set(e)) => {
+                    let Some(reply_id) = e.reply_id.as_ref().map(Uuid::from) else {
+                        continue;
+                    };
+                    let set: Option<SystemTime> = e
+                        .res
+                        .and_then(|gr| gr.set)
+                        .and_then(|t| SystemTime::try_from(t).ok());
+                    index.insert(reply_id, Loc { offset, len, set });
+                }
+                Some(Kind::Removed(u)) => {
+                    index.remove(&Uuid::from(u));
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    fn open_files(path: &Path) -> Result<(File, File), Status> {
+        let writer: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| io2status(e, "Unable to open the log"))?;
+        let reader: File = File::open(path).map_err(|e| io2status(e, "Unable to open the log"))?;
+        Ok((writer, reader))
+    }
+
+    /// Opens the log(created if missing) and rebuilds the index.
+    pub fn open<P>(path: P, compact_threshold: u64) -> Result<Self, Status>
+    where
+        P: Into<PathBuf>,
+    {
+        let path: PathBuf = path.into();
+        let index: BTreeMap<Uuid, Loc> = Self::scan(&path)?;
+        let (writer, reader) = Self::open_files(&path)?;
+        let mut store = Self {
+            path,
+            writer,
+            reader,
+            index,
+            end: 0,
+            garbage: 0,
+            compact_threshold,
+        };
+        store.compact()?;
+        Ok(store)
+    }
+
+    fn read(&mut self, loc: Loc) -> Result<Entry, Status> {
+        let mut buf: Vec<u8> = vec![0; loc.len];
+        self.reader
+            .seek(SeekFrom::Start(loc.offset))
+            .and_then(|_| self.reader.read_exact(&mut buf))
+            .map_err(|e| io2status(e, "Unable to read a record"))?;
+        let r: Record = Record::decode_length_delimited(buf.as_slice())
+            .map_err(|e| Status::data_loss(format!("broken record: {e}")))?;
+        match r.record {
+            Some(Kind::Set(e)) => Ok(e),
+            _ => Err(Status::data_loss("unexpected record")),
+        }
+    }
+
+    fn append(&mut self, r: &Record) -> Result<(u64, usize), Status> {
+        let buf: Vec<u8> = Self::encode(r)?;
+        self.writer
+            .write_all(&buf)
+            .map_err(|e| io2status(e, "Unable to write a record"))?;
+        let offset: u64 = self.end;
+        self.end += buf.len() as u64;
+        Ok((offset, buf.len()))
+    }
+
+    /// Rewrites the log using stored responses only.
+    pub fn compact(&mut self) -> Result<(), Status> {
+        let tmp: PathBuf = self.path.with_extension("compact");
+        let mut file: File =
+            File::create(&tmp).map_err(|e| io2status(e, "Unable to create a compacted log"))?;
+        let locs: Vec<(Uuid, Loc)> = self.index.iter().map(|(k, v)| (*k, *v)).collect();
+        let mut index: BTreeMap<Uuid, Loc> = BTreeMap::new();
+        let mut end: u64 = 0;
+        for (reply_id, loc) in locs {
+            let e: Entry = self.read(loc)?;
+            let buf: Vec<u8> = Self::encode(&Record {
+                record: Some(Kind::Set(e)),
+            })?;
+            file.write_all(&buf)
+                .map_err(|e| io2status(e, "Unable to write a record"))?;
+            let len: usize = buf.len();
+            index.insert(
+                reply_id,
+                Loc {
+                    offset: end,
+                    len,
+                    set: loc.set,
+                },
+            );
+            end += len as u64;
+        }
+        file.sync_all()
+            .map_err(|e| io2status(e, "Unable to sync the compacted log"))?;
+        fs::rename(&tmp, &self.path).map_err(|e| io2status(e, "Unable to replace the log"))?;
+        let (writer, reader) = Self::open_files(&self.path)?;
+        self.writer = writer;
+        self.reader = reader;
+        self.index = index;
+        self.end = end;
+        self.garbage = 0;
+        Ok(())
+    }
+
+    /// The set times of the stored responses.
+    pub fn sets(&self) -> BTreeMap<Uuid, Option<SystemTime>> {
+        self.index.iter().map(|(k, l)| (*k, l.set)).collect()
+    }
+
+    pub fn insert(&mut self, reply_id: Uuid, gr: GetResponse) -> Result<(), Status> {
+        let set: Option<SystemTime> = gr.set.clone().and_then(|t| SystemTime::try_from(t).ok());
+        let r = Record {
+            record: Some(Kind::Set(Entry {
+                reply_id: Some(reply_id.into()),
+                res: Some(gr),
+            })),
+        };
+        let (offset, len) = self.append(&r)?;
+        let prev: Option<Loc> = self.index.insert(reply_id, Loc { offset, len, set });
+        self.collect_garbage(prev.map(|_| 1).unwrap_or(0))
+    }
+
+    /// Removes the response; returns `None` if missing.
+    pub fn remove(&mut self, reply_id: &Uuid) -> Result<Option<GetResponse>, Status> {
+        let Some(loc) = self.index.get(reply_id).copied() else {
+            return Ok(None);
+        };
+        let e: Entry = self.read(loc)?;
+        let r = Record {
+            record: Some(Kind::Removed((*reply_id).into())),
+        };
+        self.append(&r)?;
+        self.index.remove(reply_id);
+        self.collect_garbage(2)?;
+        Ok(e.res)
+    }
+
+    fn collect_garbage(&mut self, records: u64) -> Result<(), Status> {
+        self.garbage += records;
+        match self.compact_threshold <= self.garbage {
+            true => self.compact(),
+            false => Ok(()),
+        }
+    }
+}
+
+pub enum Req {
+    Insert(Uuid, GetResponse, oneshot::Sender<Result<(), Status>>),
+    Remove(Uuid, oneshot::Sender<Result<Option<GetResponse>, Status>>),
+}
+
+impl Req {
+    fn handle(log: &mut Log, req: Req) {
+        let sent: bool = match req {
+            Req::Insert(reply_id, gr, reply) => reply.send(log.insert(reply_id, gr)).is_ok(),
+            Req::Remove(reply_id, reply) => reply.send(log.remove(&reply_id)).is_ok(),
+        };
+        if !sent {
+            log::warn!("Unable to send a log evt");
+        }
+    }
+}
+
+/// Opens the log and applies changes on a dedicated thread(file io blocks).
+fn log_new(
+    path: PathBuf,
+    compact_threshold: u64,
+    mut rx: Receiver<Req>,
+    opened: oneshot::Sender<Result<SetIndex, Status>>,
+) {
+    std::thread::spawn(move || {
+        let mut log: Log = match Log::open(path, compact_threshold) {
+            Ok(l) => l,
+            Err(e) => {
+                let _ = opened.send(Err(e));
+                return;
+            }
+        };
+        if opened.send(Ok(log.sets().into_iter().collect())).is_err() {
+            return;
+        }
+        while let Some(req) = rx.blocking_recv() {
+            Req::handle(&mut log, req);
+        }
+    });
+}
+
+/// A [`Store`] backed by a [`Log`]; knows the set times of the responses to answer
+/// queries without io.
+pub struct FileStore {
+    log: Sender<Req>,
+    sets: SetIndex,
+}
+
+impl FileStore {
+    /// Opens the log(created if missing); compacted after `compact_threshold` garbage records.
+    pub async fn open<P>(path: P, compact_threshold: u64) -> Result<Self, Status>
+    where
+        P: Into<PathBuf>,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let (otx, orx) = oneshot::channel();
+        log_new(path.into(), compact_threshold, rx, otx);
+        let sets: SetIndex = orx
+            .await
+            .map_err(|_| Status::internal("Unable to open the log"))??;
+        Ok(Self { log: tx, sets })
+    }
+
+    async fn call<T, F>(&self, req: F) -> Result<T, Status>
+    where
+        F: FnOnce(oneshot::Sender<Result<T, Status>>) -> Req,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.log
+            .send(req(tx))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a log request: {e}")))?;
+        rx.await.map_err(|_| Status::internal("no log evt got"))?
+    }
+}
+
+#[tonic::async_trait]
+impl Store for FileStore {
+    fn count(&self) -> usize {
+        self.sets.len()
+    }
+
+    fn contains(&self, reply_id: &Uuid) -> bool {
+        self.sets.contains(reply_id)
+    }
+
+    async fn insert(&mut self, reply_id: Uuid, gr: GetResponse) -> Result<(), Status> {
+        let set: Option<SystemTime> = gr.set.clone().and_then(|t| SystemTime::try_from(t).ok());
+        self.call(|tx| Req::Insert(reply_id, gr, tx)).await?;
+        self.sets.insert(reply_id, set);
+        Ok(())
+    }
+
+    async fn remove(&mut self, reply_id: &Uuid) -> Result<Option<GetResponse>, Status> {
+        if !self.sets.contains(reply_id) {
+            return Ok(None);
+        }
+        let id: Uuid = *reply_id;
+        let removed: Option<GetResponse> = self.call(|tx| Req::Remove(id, tx)).await?;
+        self.sets.remove(reply_id);
+        Ok(removed)
+    }
+
+    fn oldest_set(&self) -> Option<SystemTime> {
+        self.sets.oldest_set()
+    }
+
+    fn oldest(&self) -> Option<Uuid> {
+        self.sets.oldest()
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use tonic::Status;
+
+use crate::actor::ActorConfig;
+
+use crate::buffer::res::btree::svc::buf_svc_st_with;
+use crate::buffer::res::file::store::FileStore;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+
+/// Creates a buffer keeping responses in the log at the path; compacted after
+/// `compact_threshold` garbage records.
+pub async fn file_res_buffer_service_new<P>(
+    path: P,
+    max_size: usize,
+    compact_threshold: u64,
+) -> Result<impl ResBufferService, Status>
+where
+    P: Into<PathBuf>,
+{
+    file_res_buffer_service_with_config(&ActorConfig::default(), path, max_size, compact_threshold)
+        .await
+}
+
+/// Creates a buffer keeping responses in the log at the path using the config.
+pub async fn file_res_buffer_service_with_config<P>(
+    cfg: &ActorConfig,
+    path: P,
+    max_size: usize,
+    compact_threshold: u64,
+) -> Result<impl ResBufferService, Status>
+where
+    P: Into<PathBuf>,
+{
+    let store: FileStore = FileStore::open(path, compact_threshold).await?;
+    Ok(buf_svc_st_with(cfg, store, max_size).await)
+}
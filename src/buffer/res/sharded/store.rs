@@ -0,0 +1,117 @@
+use core::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tokio::sync::Notify;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::buffer::full::Full;
+use crate::buffer::res::store::{MemStore, Store};
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+
+/// The limit shared by all the shards.
+pub struct Limit {
+    used: AtomicUsize,
+    max_size: usize,
+    freed: Arc<Notify>,
+}
+
+impl Limit {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            used: AtomicUsize::new(0),
+            max_size,
+            freed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// The number of responses kept by all the shards.
+    pub fn as_used(&self) -> usize {
+        self.used.load(Ordering::Acquire)
+    }
+
+    pub fn as_max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Takes a room; returns false if all the rooms are taken.
+    fn take(&self) -> bool {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n: usize| {
+                (n < self.max_size).then_some(n + 1)
+            })
+            .is_ok()
+    }
+
+    fn release(&self) {
+        self.used.fetch_sub(1, Ordering::AcqRel);
+        self.freed.notify_waiters();
+    }
+}
+
+/// Responses of a shard counted against the [`Limit`].
+///
+/// The count is the number of the responses kept by all the shards.
+pub struct Counted {
+    own: MemStore,
+    limit: Arc<Limit>,
+}
+
+impl Counted {
+    pub fn new(limit: Arc<Limit>) -> Self {
+        Self {
+            own: MemStore::default(),
+            limit,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Store for Counted {
+    fn count(&self) -> usize {
+        self.limit.as_used()
+    }
+
+    fn contains(&self, reply_id: &Uuid) -> bool {
+        Store::contains(&self.own, reply_id)
+    }
+
+    async fn insert(&mut self, reply_id: Uuid, gr: GetResponse) -> Result<(), Status> {
+        // another shard may have taken the last room after the check of the actor
+        if !self.limit.take() {
+            let oldest_age: Option<Duration> = self
+                .own
+                .oldest_set()
+                .map(|t| SystemTime::now().duration_since(t).unwrap_or_default());
+            let (used, max) = (self.limit.as_used(), self.limit.as_max_size());
+            return Err(Full::estimate(used as u64, max as u64, oldest_age).into());
+        }
+        Store::insert(&mut self.own, reply_id, gr).await
+    }
+
+    async fn remove(&mut self, reply_id: &Uuid) -> Result<Option<GetResponse>, Status> {
+        let removed: Option<GetResponse> = Store::remove(&mut self.own, reply_id).await?;
+        if removed.is_some() {
+            self.limit.release();
+        }
+        Ok(removed)
+    }
+
+    fn oldest_set(&self) -> Option<SystemTime> {
+        self.own.oldest_set()
+    }
+
+    fn oldest(&self) -> Option<Uuid> {
+        self.own.oldest()
+    }
+
+    fn freed(&self) -> Option<Arc<Notify>> {
+        Some(self.limit.freed.clone())
+    }
+}
@@ -0,0 +1,137 @@
+use core::time::Duration;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tokio_stream::wrappers::ReceiverStream;
+
+use tonic::{Request, Response, Status};
+
+use crate::retry::deadline;
+use crate::uuid::Uuid;
+
+use crate::actor::ActorConfig;
+use crate::buffer::res::btree::svc::{buf_svc_st_with, BufSvcSt};
+use crate::buffer::res::cmd::del::DelReq;
+use crate::buffer::res::cmd::get::GetReq;
+use crate::buffer::res::cmd::set::SetReq;
+use crate::buffer::res::cmd::stats::ResStats;
+use crate::buffer::res::sharded::store::{Counted, Limit};
+
+use crate::rpc::perf::helper;
+
+use helper::proto::buffer::v1::res_buf::{DelRequest, DelResponse};
+use helper::proto::buffer::v1::res_buf::{GetRequest, GetResponse};
+use helper::proto::buffer::v1::res_buf::{LenRequest, LenResponse};
+use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+
+/// Buffers(actors) selected by the hash of the reply id.
+///
+/// The shards share `max_size`: the buffer is full(`Unavailable`) once all the shards
+/// keep `max_size` responses in total.
+/// A shard drops its own oldest response if the policy is [`FullPolicy::DropOldest`].
+///
+/// [`FullPolicy::DropOldest`]: crate::actor::FullPolicy::DropOldest
+pub struct Sharded {
+    shards: Vec<BufSvcSt>,
+    limit: Arc<Limit>,
+}
+
+impl Sharded {
+    pub fn shard(&self, reply_id: Uuid) -> &BufSvcSt {
+        let mut h = DefaultHasher::new();
+        reply_id.hash(&mut h);
+        let ix: usize = (h.finish() % self.shards.len() as u64) as usize;
+        &self.shards[ix]
+    }
+
+    pub fn count(&self) -> u64 {
+        self.limit.as_used() as u64
+    }
+
+    pub async fn stats(&self) -> Result<ResStats, Status> {
+        let mut oldest_age: Option<Duration> = None;
+        for s in &self.shards {
+            let st: ResStats = s.stats().await?;
+            oldest_age = oldest_age.max(st.as_oldest_age());
+        }
+        let max_size: u64 = self.limit.as_max_size() as u64;
+        Ok(ResStats::new(max_size, self.count(), oldest_age))
+    }
+}
+
+#[tonic::async_trait]
+impl ResBufferService for Sharded {
+    type GetStream = ReceiverStream<Result<GetResponse, Status>>;
+
+    async fn get(&self, req: Request<GetRequest>) -> Result<Response<Self::GetStream>, Status> {
+        let deadline: Option<Duration> = deadline::from_metadata(req.metadata());
+        let gr: GetRequest = req.into_inner();
+        let checked: GetReq = GetReq::try_from(gr)?.within(deadline);
+        let shard: &BufSvcSt = self.shard(checked.as_reply_id());
+        let reply = shard.get(checked).await?;
+        Ok(Response::new(reply))
+    }
+
+    async fn set(&self, req: Request<SetRequest>) -> Result<Response<SetResponse>, Status> {
+        let sr: SetRequest = req.into_inner();
+        let checked: SetReq = sr.try_into()?;
+        let shard: &BufSvcSt = self.shard(checked.as_reply_id());
+        let set: SystemTime = shard.set(checked).await?;
+        let reply = SetResponse {
+            set: Some(set.into()),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn del(&self, req: Request<DelRequest>) -> Result<Response<DelResponse>, Status> {
+        let dr: DelRequest = req.into_inner();
+        let checked: DelReq = dr.try_into()?;
+        let reply_id: Uuid = checked.as_reply_id();
+        let removed: SystemTime = self.shard(reply_id).del(reply_id).await?;
+        let reply = DelResponse {
+            removed: Some(removed.into()),
+        };
+        Ok(Response::new(reply))
+    }
+
+    async fn len(&self, _req: Request<LenRequest>) -> Result<Response<LenResponse>, Status> {
+        let length: u64 = self.count();
+        let reply = LenResponse { length };
+        Ok(Response::new(reply))
+    }
+
+    async fn stats(&self, _req: Request<StatsRequest>) -> Result<Response<StatsResponse>, Status> {
+        let stats: ResStats = self.stats().await?;
+        Ok(Response::new(stats.into()))
+    }
+}
+
+/// Creates a buffer using `shards` actors keeping up to `max_size` responses in total.
+pub async fn sharded_res_buffer_service_new(
+    shards: usize,
+    max_size: usize,
+) -> Result<impl ResBufferService, Status> {
+    sharded_res_buffer_service_with_config(&ActorConfig::default(), shards, max_size).await
+}
+
+/// Creates a sharded buffer whose shards use the config.
+pub async fn sharded_res_buffer_service_with_config(
+    cfg: &ActorConfig,
+    shards: usize,
+    max_size: usize,
+) -> Result<impl ResBufferService, Status> {
+    if shards < 1 {
+        return Err(Status::invalid_argument("at least one shard required"));
+    }
+    let limit: Arc<Limit> = Arc::new(Limit::new(max_size));
+    let mut v: Vec<BufSvcSt> = Vec::with_capacity(shards);
+    for _ in 0..shards {
+        let store: Counted = Counted::new(limit.clone());
+        v.push(buf_svc_st_with(cfg, store, max_size).await);
+    }
+    Ok(Sharded { shards: v, limit })
+}
@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tokio::sync::Notify;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+
+/// Responses waiting for gets.
+///
+/// Changes are async so that a store doing io does not block the actor thread.
+#[tonic::async_trait]
+pub trait Store: Send + Sync + 'static {
+    fn count(&self) -> usize;
+
+    fn contains(&self, reply_id: &Uuid) -> bool;
+
+    async fn insert(&mut self, reply_id: Uuid, gr: GetResponse) -> Result<(), Status>;
+
+    /// Removes the response; returns `None` if missing.
+    async fn remove(&mut self, reply_id: &Uuid) -> Result<Option<GetResponse>, Status>;
+
+    /// The set time of the oldest response.
+    fn oldest_set(&self) -> Option<SystemTime>;
+
+    /// The reply id of the oldest response(responses without the set time go last).
+    fn oldest(&self) -> Option<Uuid>;
+
+    /// Notified when a store sharing the limit with this one removes a response.
+    fn freed(&self) -> Option<Arc<Notify>> {
+        None
+    }
+}
+
+/// Set times of responses ordered oldest first(responses without the set time go last).
+#[derive(Default)]
+pub struct SetIndex {
+    sets: BTreeMap<Uuid, Option<SystemTime>>,
+    order: BTreeSet<(bool, Option<SystemTime>, Uuid)>,
+}
+
+impl SetIndex {
+    fn key(reply_id: Uuid, set: Option<SystemTime>) -> (bool, Option<SystemTime>, Uuid) {
+        (set.is_none(), set, reply_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    pub fn contains(&self, reply_id: &Uuid) -> bool {
+        self.sets.contains_key(reply_id)
+    }
+
+    pub fn insert(&mut self, reply_id: Uuid, set: Option<SystemTime>) {
+        if let Some(old) = self.sets.insert(reply_id, set) {
+            self.order.remove(&Self::key(reply_id, old));
+        }
+        self.order.insert(Self::key(reply_id, set));
+    }
+
+    /// Removes the response; returns false if missing.
+    pub fn remove(&mut self, reply_id: &Uuid) -> bool {
+        let Some(set) = self.sets.remove(reply_id) else {
+            return false;
+        };
+        self.order.remove(&Self::key(*reply_id, set))
+    }
+
+    pub fn oldest_set(&self) -> Option<SystemTime> {
+        self.order.first().and_then(|k| k.1)
+    }
+
+    pub fn oldest(&self) -> Option<Uuid> {
+        self.order.first().map(|k| k.2)
+    }
+}
+
+impl FromIterator<(Uuid, Option<SystemTime>)> for SetIndex {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Uuid, Option<SystemTime>)>,
+    {
+        let mut ix = Self::default();
+        for (reply_id, set) in iter {
+            ix.insert(reply_id, set);
+        }
+        ix
+    }
+}
+
+/// Responses kept in memory.
+#[derive(Default)]
+pub struct MemStore {
+    responses: BTreeMap<Uuid, GetResponse>,
+    index: SetIndex,
+}
+
+#[tonic::async_trait]
+impl Store for MemStore {
+    fn count(&self) -> usize {
+        self.responses.len()
+    }
+
+    fn contains(&self, reply_id: &Uuid) -> bool {
+        self.responses.contains_key(reply_id)
+    }
+
+    async fn insert(&mut self, reply_id: Uuid, gr: GetResponse) -> Result<(), Status> {
+        let set: Option<SystemTime> = gr.set.clone().and_then(|t| SystemTime::try_from(t).ok());
+        self.responses.insert(reply_id, gr);
+        self.index.insert(reply_id, set);
+        Ok(())
+    }
+
+    async fn remove(&mut self, reply_id: &Uuid) -> Result<Option<GetResponse>, Status> {
+        self.index.remove(reply_id);
+        Ok(self.responses.remove(reply_id))
+    }
+
+    fn oldest_set(&self) -> Option<SystemTime> {
+        self.index.oldest_set()
+    }
+
+    fn oldest(&self) -> Option<Uuid> {
+        self.index.oldest()
+    }
+}
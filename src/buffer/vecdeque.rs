@@ -1 +1,3 @@
+pub mod dead;
+pub mod inflight;
 pub mod svc;
@@ -0,0 +1,113 @@
+use core::time::Duration;
+use std::time::SystemTime;
+
+use tonic::{Request, Response, Status};
+
+use crate::actor::{ActorConfig, FullPolicy};
+use crate::uuid::Uuid;
+
+use crate::buffer::cmd::dead::req::{ListReq, RequeueReq};
+use crate::buffer::cmd::save::req::SaveInfo;
+use crate::buffer::vecdeque::svc::{buf_svc_st_new, BufSvcSt, DeadLetter};
+
+use crate::rpc::perf::helper;
+
+use helper::proto::buffer::v1::dead_letter::{Entry, ListRequest, ListResponse};
+use helper::proto::buffer::v1::dead_letter::{RequeueRequest, RequeueResponse};
+use helper::proto::buffer::v1::dead_letter_service_server::DeadLetterService;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+
+/// Lists and requeues requests in the dead letter buffer.
+pub struct DeadLetters {
+    dead: BufSvcSt,
+    live: BufSvcSt,
+}
+
+impl DeadLetters {
+    /// The dead letter buffer(dead requests can also be loaded from it).
+    pub fn buffer(&self) -> impl ReqBufferService {
+        self.dead.clone()
+    }
+
+    /// Moves the dead request back to the live buffer.
+    pub async fn revive(&self, reply_id: Uuid) -> Result<SystemTime, Status> {
+        let si: SaveInfo = BufSvcSt::take(self.dead.as_sender(), reply_id).await?;
+        let revived: SaveInfo = si.clone().revived();
+        match BufSvcSt::save(self.live.as_sender(), revived).await {
+            Ok(saved) => Ok(saved),
+            Err(e) => {
+                if let Err(e) = BufSvcSt::save(self.dead.as_sender(), si).await {
+                    log::warn!("Unable to put back a dead request. reply id: {reply_id}: {e}");
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl DeadLetterService for DeadLetters {
+    async fn list(&self, req: Request<ListRequest>) -> Result<Response<ListResponse>, Status> {
+        let lr: ListRequest = req.into_inner();
+        let checked: ListReq = lr.try_into()?;
+        let listed: Vec<SaveInfo> =
+            BufSvcSt::list(self.dead.as_sender(), checked.as_max_count()).await?;
+        let entries: Vec<Entry> = listed.into_iter().map(|si| si.into()).collect();
+        Ok(Response::new(ListResponse { entries }))
+    }
+
+    async fn requeue(
+        &self,
+        req: Request<RequeueRequest>,
+    ) -> Result<Response<RequeueResponse>, Status> {
+        let rr: RequeueRequest = req.into_inner();
+        let checked: RequeueReq = rr.try_into()?;
+        let saved: SystemTime = self.revive(checked.as_reply_id()).await?;
+        let reply = RequeueResponse {
+            saved: Some(saved.into()),
+        };
+        Ok(Response::new(reply))
+    }
+}
+
+/// Creates a buffer which moves requests delivered `max_deliveries` times(or rejected) to a
+/// dead letter buffer.
+pub async fn dead_lettered_request_buffer_service_new(
+    max_buf_size: usize,
+    visibility: Duration,
+    max_deliveries: u64,
+    max_dead_size: usize,
+) -> (impl ReqBufferService, DeadLetters) {
+    let cfg = ActorConfig::default();
+    dead_lettered_request_buffer_service_with_config(
+        &cfg,
+        max_buf_size,
+        visibility,
+        max_deliveries,
+        max_dead_size,
+    )
+    .await
+}
+
+/// Creates a dead lettered buffer using the config.
+///
+/// The dead letter buffer always rejects requests once full(the requests are dropped and
+/// counted): blocking would stall the live buffer, dropping the oldest would lose them
+/// silently.
+pub async fn dead_lettered_request_buffer_service_with_config(
+    cfg: &ActorConfig,
+    max_buf_size: usize,
+    visibility: Duration,
+    max_deliveries: u64,
+    max_dead_size: usize,
+) -> (impl ReqBufferService, DeadLetters) {
+    let dead_cfg = ActorConfig::new(cfg.as_depth(), FullPolicy::Reject);
+    let dead: BufSvcSt = buf_svc_st_new(&dead_cfg, max_dead_size, None, None).await;
+    let dl: DeadLetter = DeadLetter::new(max_deliveries, dead.clone());
+    let live: BufSvcSt = buf_svc_st_new(cfg, max_buf_size, Some(visibility), Some(dl)).await;
+    let letters = DeadLetters {
+        dead,
+        live: live.clone(),
+    };
+    (live, letters)
+}
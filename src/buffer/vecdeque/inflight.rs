@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use tokio::time::Instant;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::buffer::cmd::save::req::SaveInfo;
+
+/// Delivered requests waiting for acks; unacked requests are found in deadline order.
+#[derive(Default)]
+pub struct InFlight {
+    entries: BTreeMap<Uuid, (Instant, SaveInfo)>,
+    ordered: BTreeSet<(Instant, Uuid)>,
+}
+
+impl InFlight {
+    pub fn insert(&mut self, id: Uuid, si: SaveInfo, deadline: Instant) {
+        if let Some((prev, _)) = self.entries.insert(id, (deadline, si)) {
+            self.ordered.remove(&(prev, id));
+        }
+        self.ordered.insert((deadline, id));
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Result<SaveInfo, Status> {
+        let (deadline, si) = self
+            .entries
+            .remove(&id)
+            .ok_or_else(|| Status::not_found(format!("no such delivery: {id}")))?;
+        self.ordered.remove(&(deadline, id));
+        Ok(si)
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.ordered.first().map(|t| t.0)
+    }
+
+    /// Removes requests whose deadlines passed(oldest deadline first).
+    pub fn expired(&mut self, now: Instant) -> Vec<SaveInfo> {
+        let mut expired: Vec<SaveInfo> = vec![];
+        while let Some(&(deadline, id)) = self.ordered.first() {
+            if now < deadline {
+                break;
+            }
+            self.ordered.pop_first();
+            if let Some((_, si)) = self.entries.remove(&id) {
+                expired.push(si);
+            }
+        }
+        expired
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -1,48 +1,270 @@
+use core::future::Future;
 use core::time::Duration;
 use std::collections::VecDeque;
-use std::time::{Instant, SystemTime};
+use std::time::SystemTime;
 
 use tokio::sync::mpsc::Sender;
-use tokio::time::Interval;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
 use tokio_stream::wrappers::ReceiverStream;
 
 use tonic::{Code, Request, Response, Status};
 
-use crate::uuid::Uuid;
-
+use crate::actor::{ActorConfig, FullPolicy};
+use crate::retry::deadline;
 use crate::retry::Retry;
+use crate::uuid::Uuid;
 
+use crate::buffer::blocked::Blocked;
+use crate::buffer::cmd::ack::req::{AckReq, RejectReq};
 use crate::buffer::cmd::load::req::LoadReq;
+use crate::buffer::cmd::load::res::Delivery;
 use crate::buffer::cmd::save::req::{SaveInfo, SaveReq};
+use crate::buffer::cmd::stats::ReqStats;
+use crate::buffer::full::Full;
+use crate::buffer::vecdeque::inflight::InFlight;
 
 use crate::rpc::perf::helper;
 
+use helper::proto::buffer::v1::req_buf::{AckRequest, AckResponse};
 use helper::proto::buffer::v1::req_buf::{LoadRequest, LoadResponse};
+use helper::proto::buffer::v1::req_buf::{NackRequest, NackResponse};
+use helper::proto::buffer::v1::req_buf::{RejectRequest, RejectResponse};
 use helper::proto::buffer::v1::req_buf::{SaveRequest, SaveResponse};
+use helper::proto::buffer::v1::req_buf::{StatsRequest, StatsResponse};
 use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
 
-pub enum Req {
-    PushBack(SaveInfo, Sender<Result<SystemTime, Status>>),
-    PopFront(Sender<Result<SaveInfo, Status>>),
+/// Loads waiting for requests(oldest first).
+pub type Waiters = VecDeque<oneshot::Sender<Result<Delivery, Status>>>;
+
+/// Saves waiting for room.
+pub type Parked = Blocked<(SaveInfo, Sender<Result<SystemTime, Status>>)>;
+
+/// Where requests which can not be converted go.
+pub struct DeadLetter {
+    max_deliveries: u64,
+    buf: BufSvcSt,
 }
 
-impl Req {
-    async fn handle_save(
-        mv: &mut VecDeque<SaveInfo>,
-        si: SaveInfo,
-        reply: Sender<Result<SystemTime, Status>>,
+impl DeadLetter {
+    /// Moves requests delivered `max_deliveries` times(or rejected) to the buffer.
+    pub fn new(max_deliveries: u64, buf: BufSvcSt) -> Self {
+        Self {
+            max_deliveries,
+            buf,
+        }
+    }
+
+    pub fn is_exhausted(&self, si: &SaveInfo) -> bool {
+        self.max_deliveries <= si.as_deliveries()
+    }
+}
+
+/// Queued requests, waiting loads and delivered requests waiting for acks.
+pub struct State {
+    queue: VecDeque<SaveInfo>,
+    waiters: Waiters,
+    inflight: InFlight,
+    max_size: usize,
+    full: FullPolicy,
+    parked: Parked,
+    visibility: Option<Duration>,
+    dead: Option<DeadLetter>,
+    dropped: u64,
+}
+
+impl State {
+    /// Creates an empty state; delivered requests must be acked if the visibility is set.
+    pub fn new(
         max_size: usize,
-    ) {
-        let sz: usize = mv.len();
-        let too_many: bool = max_size < sz;
-        let r = match too_many {
-            true => Err(Status::unavailable(format!(
-                "too many requests. size: {sz}"
-            ))),
+        full: FullPolicy,
+        visibility: Option<Duration>,
+        dead: Option<DeadLetter>,
+    ) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            waiters: VecDeque::new(),
+            inflight: InFlight::default(),
+            max_size,
+            full,
+            parked: Blocked::default(),
+            visibility,
+            dead,
+            dropped: 0,
+        }
+    }
+
+    /// Number of queued requests and delivered requests waiting for acks.
+    fn len(&self) -> usize {
+        self.queue.len() + self.inflight.len()
+    }
+
+    /// Checks if the queue and the delivered requests waiting for acks hold `max_size` requests.
+    fn is_full(&self) -> bool {
+        self.max_size <= self.len()
+    }
+
+    fn oldest_age(&self, now: SystemTime) -> Option<Duration> {
+        let oldest: SystemTime = self.queue.front()?.as_saved();
+        Some(now.duration_since(oldest).unwrap_or_default())
+    }
+
+    fn too_many(&self) -> Status {
+        let sz: u64 = self.len() as u64;
+        let oldest_age: Option<Duration> = self.oldest_age(SystemTime::now());
+        Full::estimate(sz, self.max_size as u64, oldest_age).into()
+    }
+
+    /// The earliest deadline of delivered requests or parked saves.
+    fn next_deadline(&self) -> Option<Instant> {
+        let inflight: Option<Instant> = self.inflight.next_deadline();
+        let parked: Option<Instant> = self.parked.next_deadline();
+        match (inflight, parked) {
+            (Some(i), Some(p)) => Some(i.min(p)),
+            (i, p) => i.or(p),
+        }
+    }
+
+    /// Saves parked requests while the queue has room.
+    async fn admit(&mut self) {
+        while !self.is_full() {
+            let Some((si, reply)) = self.parked.pop() else {
+                return;
+            };
+            if reply.is_closed() {
+                continue;
+            }
+            let saved: SystemTime = si.as_saved();
+            if let Some(si) = self.handoff(si) {
+                self.queue.push_back(si);
+            }
+            match reply.send(Ok(saved)).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a save evt: {e}"),
+            }
+        }
+    }
+
+    fn is_exhausted(&self, si: &SaveInfo) -> bool {
+        self.dead
+            .as_ref()
+            .map(|d| d.is_exhausted(si))
+            .unwrap_or(false)
+    }
+
+    /// Moves the request to the dead letter buffer; dropped(and counted) if missing.
+    ///
+    /// Fails(the request is dropped and counted) if the dead letter buffer rejected it.
+    async fn bury(&mut self, si: SaveInfo, reason: String) -> Result<(), Status> {
+        let reply_id: Uuid = si.as_req().as_reply_id();
+        let Some(dead) = self.dead.as_ref() else {
+            log::warn!("dropped a dead request. reply id: {reply_id}, reason: {reason}");
+            self.dropped += 1;
+            return Ok(());
+        };
+        let r: Result<_, _> = BufSvcSt::save(&dead.buf.sender, si.rejected(reason)).await;
+        r.map(|_| ()).map_err(|e| {
+            log::warn!("Unable to save a dead request. reply id: {reply_id}, error: {e}");
+            self.dropped += 1;
+            Status::data_loss(format!("dropped a dead request. reply id: {reply_id}: {e}"))
+        })
+    }
+
+    /// Redelivers the request unless it was delivered too many times.
+    async fn retry(&mut self, si: SaveInfo) -> Result<(), Status> {
+        match self.is_exhausted(&si) {
+            true => {
+                let reason: String = format!("delivered {} times", si.as_deliveries());
+                self.bury(si, reason).await
+            }
             false => {
-                mv.push_back(si);
-                Ok(SystemTime::now())
+                self.requeue(si);
+                Ok(())
+            }
+        }
+    }
+
+    /// Hands the request over using the send; returns the request if not delivered.
+    fn deliver<F>(&mut self, si: SaveInfo, send: F) -> Option<SaveInfo>
+    where
+        F: FnOnce(Result<Delivery, Status>) -> Result<(), Result<Delivery, Status>>,
+    {
+        let d: Delivery = Delivery::new(self.visibility.map(|_| Uuid::generate()), si);
+        let kept: Option<(Uuid, SaveInfo, Instant)> = d.as_id().zip(self.visibility).map(|p| {
+            let (id, visibility) = p;
+            let si: SaveInfo = d.as_info().clone().delivered();
+            (id, si, Instant::now() + visibility)
+        });
+        match send(Ok(d)) {
+            Ok(_) => {
+                if let Some((id, si, deadline)) = kept {
+                    self.inflight.insert(id, si, deadline);
+                }
+                None
             }
+            Err(r) => r.ok().map(Delivery::into_info),
+        }
+    }
+
+    /// Hands the request to the oldest waiting load(if any); returns the request otherwise.
+    fn handoff(&mut self, si: SaveInfo) -> Option<SaveInfo> {
+        let mut si: SaveInfo = si;
+        while let Some(waiter) = self.waiters.pop_front() {
+            match self.deliver(si, |r| waiter.send(r)) {
+                None => return None,
+                Some(back) => si = back,
+            }
+        }
+        Some(si)
+    }
+
+    /// Puts back the request to be delivered next.
+    fn requeue(&mut self, si: SaveInfo) {
+        if let Some(si) = self.handoff(si) {
+            self.queue.push_front(si);
+        }
+    }
+}
+
+pub enum Req {
+    PushBack(SaveInfo, Sender<Result<SystemTime, Status>>),
+    PushFront(Vec<SaveInfo>),
+    PopFront(Sender<Result<Delivery, Status>>),
+    Wait(oneshot::Sender<Result<Delivery, Status>>),
+    Unwait,
+    Ack(Uuid, Sender<Result<(), Status>>),
+    Nack(Uuid, Sender<Result<(), Status>>),
+    Reject(Uuid, String, Sender<Result<(), Status>>),
+    List(usize, Sender<Vec<SaveInfo>>),
+    Take(Uuid, Sender<Result<SaveInfo, Status>>),
+    Stats(Sender<Result<ReqStats, Status>>),
+}
+
+impl Req {
+    async fn handle_save(st: &mut State, si: SaveInfo, reply: Sender<Result<SystemTime, Status>>) {
+        let saved: SystemTime = si.as_saved();
+        let r: Result<_, _> = match st.handoff(si) {
+            None => Ok(saved),
+            Some(si) => match (st.is_full(), st.full) {
+                (false, _) => {
+                    st.queue.push_back(si);
+                    Ok(saved)
+                }
+                (true, FullPolicy::Reject) => Err(st.too_many()),
+                (true, FullPolicy::DropOldest) => match st.queue.pop_front() {
+                    None => Err(st.too_many()),
+                    Some(old) => {
+                        let reply_id: Uuid = old.as_req().as_reply_id();
+                        log::warn!("dropped the oldest request. reply id: {reply_id}");
+                        st.queue.push_back(si);
+                        Ok(saved)
+                    }
+                },
+                (true, FullPolicy::Block(wait)) => {
+                    st.parked.park(Instant::now() + wait, (si, reply));
+                    return;
+                }
+            },
         };
         match reply.send(r).await {
             Ok(_) => {}
@@ -50,18 +272,151 @@ impl Req {
         }
     }
 
-    async fn handle_get(mv: &mut VecDeque<SaveInfo>, reply: Sender<Result<SaveInfo, Status>>) {
-        let r = match mv.pop_front() {
-            None => Err(Status::not_found("no request for now. try again")),
-            Some(si) => Ok(si),
+    /// Puts back the requests(oldest first) to be delivered before the others.
+    fn handle_push_front(st: &mut State, v: Vec<SaveInfo>) {
+        for si in v.into_iter().rev() {
+            st.queue.push_front(si);
+        }
+        while !st.waiters.is_empty() {
+            let Some(si) = st.queue.pop_front() else {
+                return;
+            };
+            if let Some(si) = st.handoff(si) {
+                st.queue.push_front(si);
+            }
+        }
+    }
+
+    fn handle_get(st: &mut State, reply: Sender<Result<Delivery, Status>>) {
+        let Some(si) = st.queue.pop_front() else {
+            let r = reply.try_send(Err(Status::not_found("no request for now. try again")));
+            if let Err(e) = r {
+                log::warn!("Unable to send a get evt: {e}");
+            }
+            return;
+        };
+        if let Some(si) = st.deliver(si, |r| reply.try_send(r).map_err(|e| e.into_inner())) {
+            st.queue.push_front(si);
+        }
+    }
+
+    fn handle_wait(st: &mut State, reply: oneshot::Sender<Result<Delivery, Status>>) {
+        let Some(si) = st.queue.pop_front() else {
+            st.waiters.push_back(reply);
+            return;
         };
+        if let Some(si) = st.deliver(si, |r| reply.send(r)) {
+            st.queue.push_front(si);
+        }
+    }
+
+    /// Removes waiters which gave up.
+    fn handle_unwait(st: &mut State) {
+        st.waiters.retain(|o| !o.is_closed());
+    }
+
+    async fn handle_ack(st: &mut State, id: Uuid, reply: Sender<Result<(), Status>>) {
+        let r: Result<(), _> = st.inflight.remove(id).map(|_| ());
         match reply.send(r).await {
             Ok(_) => {}
-            Err(e) => log::warn!("Unable to send a get evt: {e}"),
+            Err(e) => log::warn!("Unable to send an ack evt: {e}"),
+        }
+    }
+
+    async fn handle_nack(st: &mut State, id: Uuid, reply: Sender<Result<(), Status>>) {
+        let r: Result<(), _> = match st.inflight.remove(id) {
+            Ok(si) => st.retry(si).await,
+            Err(e) => Err(e),
+        };
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a nack evt: {e}"),
+        }
+    }
+
+    async fn handle_reject(
+        st: &mut State,
+        id: Uuid,
+        reason: String,
+        reply: Sender<Result<(), Status>>,
+    ) {
+        let r: Result<(), _> = match st.inflight.remove(id) {
+            Ok(si) => st.bury(si, reason).await,
+            Err(e) => Err(e),
+        };
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a reject evt: {e}"),
+        }
+    }
+
+    async fn handle_list(st: &State, max_count: usize, reply: Sender<Vec<SaveInfo>>) {
+        let limit: usize = match max_count {
+            0 => usize::MAX,
+            n => n,
+        };
+        let listed: Vec<SaveInfo> = st.queue.iter().take(limit).cloned().collect();
+        match reply.send(listed).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a list evt: {e}"),
+        }
+    }
+
+    async fn handle_take(st: &mut State, reply_id: Uuid, reply: Sender<Result<SaveInfo, Status>>) {
+        let r: Result<SaveInfo, _> = st
+            .queue
+            .iter()
+            .position(|si| si.as_req().as_reply_id() == reply_id)
+            .and_then(|pos| st.queue.remove(pos))
+            .ok_or_else(|| Status::not_found(format!("no such request. reply id: {reply_id}")));
+        match reply.send(r).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a take evt: {e}"),
+        }
+    }
+
+    async fn handle_stats(st: &State, reply: Sender<Result<ReqStats, Status>>) {
+        let stats = ReqStats::new(
+            st.max_size as u64,
+            st.queue.len() as u64,
+            st.oldest_age(SystemTime::now()),
+            st.inflight.len() as u64,
+        )
+        .dropped(st.dropped);
+        match reply.send(Ok(stats)).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send a stats evt: {e}"),
+        }
+    }
+
+    /// Rejects parked saves which waited too long.
+    async fn handle_parked(st: &mut State, now: Instant) {
+        for (_, reply) in st.parked.expired(now) {
+            match reply.send(Err(st.too_many())).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a save evt: {e}"),
+            }
+        }
+    }
+
+    /// Redelivers requests not acked within the visibility timeout.
+    async fn handle_expired(st: &mut State, now: Instant) {
+        let expired: Vec<SaveInfo> = st.inflight.expired(now);
+        let mut rest: Vec<SaveInfo> = vec![];
+        for si in expired {
+            match st.is_exhausted(&si) {
+                // counted in the stats if dropped
+                true => st.retry(si).await.unwrap_or_default(),
+                false => rest.extend(st.handoff(si)),
+            }
+        }
+        for si in rest.into_iter().rev() {
+            st.queue.push_front(si);
         }
     }
 }
 
+#[derive(Clone)]
 pub struct BufSvcSt {
     sender: Sender<Req>,
 }
@@ -80,29 +435,134 @@ impl BufSvcSt {
         }
     }
 
-    pub async fn try_get(
+    /// Puts back the requests(oldest first) to be delivered next.
+    pub async fn push_front(sender: &Sender<Req>, v: Vec<SaveInfo>) -> Result<(), Status> {
+        sender
+            .send(Req::PushFront(v))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a push request: {e}")))
+    }
+
+    /// Waits for a request until it is saved or the timeout elapses.
+    pub async fn wait(sender: &Sender<Req>, timeout: Duration) -> Result<Delivery, Status> {
+        Self::wait_or(sender, timeout, std::future::pending()).await
+    }
+
+    /// Waits for a request until it is saved, the timeout elapses or the cancel completes.
+    pub async fn wait_or<F>(
         sender: &Sender<Req>,
-        interval: &mut Interval,
         timeout: Duration,
-        i: u64,
-        started: Instant,
-    ) -> Result<SaveInfo, Status> {
-        interval.tick().await;
-        let elapsed: Duration = Instant::now()
-            .checked_duration_since(started)
-            .ok_or_else(|| Status::internal("CLOCK BROKEN!!!"))?;
-        let too_slow: bool = timeout < elapsed;
-        (!too_slow).then_some(()).ok_or_else(|| {
-            Status::deadline_exceeded(format!("timeout. elapsed={elapsed:#?}, tried: {i}"))
-        })?;
-        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
-        let req = Req::PopFront(tx);
+        cancel: F,
+    ) -> Result<Delivery, Status>
+    where
+        F: Future<Output = ()>,
+    {
+        let (tx, mut rx) = oneshot::channel();
+        let req = Req::Wait(tx);
         sender
             .send(req)
             .await
-            .map_err(|e| Status::internal(format!("Unable to send a get request: {e}")))?;
+            .map_err(|e| Status::internal(format!("Unable to send a wait request: {e}")))?;
+        let gaveup: Status = tokio::select! {
+            biased;
+            r = &mut rx => return r.unwrap_or_else(|_| Err(Status::internal("No reply got"))),
+            _ = tokio::time::sleep(timeout) => Status::deadline_exceeded(format!(
+                "timeout. elapsed={timeout:#?}"
+            )),
+            _ = cancel => Status::cancelled("load cancelled"),
+        };
+
+        // a request handed off just before giving up must not be lost
+        rx.close();
+        if let Ok(r) = rx.try_recv() {
+            return r;
+        }
+
+        match sender.send(Req::Unwait).await {
+            Ok(_) => {}
+            Err(e) => log::warn!("Unable to send an unwait request: {e}"),
+        }
+        Err(gaveup)
+    }
+
+    async fn settle(sender: &Sender<Req>, req: Req) -> Result<(), Status> {
+        sender
+            .send(req)
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send an ack request: {e}")))
+    }
+
+    /// Removes the delivered request.
+    pub async fn ack(sender: &Sender<Req>, delivery_id: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        Self::settle(sender, Req::Ack(delivery_id, tx)).await?;
+        match rx.recv().await {
+            None => Err(Status::internal("Unable to ack")),
+            Some(r) => r,
+        }
+    }
+
+    /// Moves the delivered request to the dead letter buffer.
+    pub async fn reject(
+        sender: &Sender<Req>,
+        delivery_id: Uuid,
+        reason: String,
+    ) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        Self::settle(sender, Req::Reject(delivery_id, reason, tx)).await?;
+        match rx.recv().await {
+            None => Err(Status::internal("Unable to reject")),
+            Some(r) => r,
+        }
+    }
+
+    /// Lists queued requests(oldest first; max_count 0: all).
+    pub async fn list(sender: &Sender<Req>, max_count: usize) -> Result<Vec<SaveInfo>, Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        sender
+            .send(Req::List(max_count, tx))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a list request: {e}")))?;
+        rx.recv()
+            .await
+            .ok_or_else(|| Status::internal("Unable to list"))
+    }
+
+    /// Removes the queued request for the reply id.
+    pub async fn take(sender: &Sender<Req>, reply_id: Uuid) -> Result<SaveInfo, Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        sender
+            .send(Req::Take(reply_id, tx))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a take request: {e}")))?;
+        match rx.recv().await {
+            None => Err(Status::internal("Unable to take")),
+            Some(r) => r,
+        }
+    }
+
+    pub async fn stats(sender: &Sender<Req>) -> Result<ReqStats, Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        sender
+            .send(Req::Stats(tx))
+            .await
+            .map_err(|e| Status::internal(format!("Unable to send a stats request: {e}")))?;
+        match rx.recv().await {
+            None => Err(Status::internal("Unable to get stats")),
+            Some(r) => r,
+        }
+    }
+
+    pub(crate) fn as_sender(&self) -> &Sender<Req> {
+        &self.sender
+    }
+
+    /// Puts back the delivered request to be redelivered.
+    pub async fn nack(sender: &Sender<Req>, delivery_id: Uuid) -> Result<(), Status> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        Self::settle(sender, Req::Nack(delivery_id, tx)).await?;
         match rx.recv().await {
-            None => Err(Status::internal("No reply got")),
+            None => Err(Status::internal("Unable to nack")),
             Some(r) => r,
         }
     }
@@ -125,82 +585,156 @@ impl ReqBufferService for BufSvcSt {
     }
 
     async fn load(&self, req: Request<LoadRequest>) -> Result<Response<Self::LoadStream>, Status> {
+        let deadline: Option<Duration> = deadline::from_metadata(req.metadata());
+        let until: Option<Instant> = deadline.map(|d: Duration| Instant::now() + d);
         let lr: LoadRequest = req.into_inner();
-        let checked: LoadReq = lr.try_into()?;
+        let checked: LoadReq = LoadReq::try_from(lr)?.within(deadline);
 
         let retry: &Retry = checked.as_retry();
-
-        let retry_max: u64 = retry.as_retry_max();
-        let interval: Duration = retry.as_interval();
         let timeout: Duration = retry.as_timeout();
+        let max_count: u64 = checked.as_max_count();
+        let streaming: bool = checked.is_streaming();
 
         let sender: Sender<Req> = self.sender.clone();
 
-        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let (tx, rx) = tokio::sync::mpsc::channel(checked.as_window());
 
         tokio::spawn(async move {
-            let mut invl: Interval = tokio::time::interval(interval);
-            let started: Instant = Instant::now();
-            for i in 0..retry_max {
-                match Self::try_get(&sender, &mut invl, timeout, i, started).await {
-                    Ok(si) => {
-                        let saved: SystemTime = si.as_saved();
-                        let req: SaveReq = si.into_req();
-                        let reply_id: Uuid = req.as_reply_id();
-                        let received = req.as_received().clone();
-                        let reply = LoadResponse {
-                            req: Some(req.into_request()),
-                            reply_id: Some(reply_id.into()),
-                            received: Some(received),
-                            saved: Some(saved.into()),
-                        };
-                        match tx.send(Ok(reply)).await {
-                            Ok(_) => {}
-                            Err(e) => {
-                                log::warn!("Unable to send a reply: {e}");
-                            }
+            for _ in 0..max_count {
+                // waits for a credit before taking a request from the queue
+                let permit = match tx.reserve().await {
+                    Ok(p) => p,
+                    Err(_) => return,
+                };
+                // a stream waits no longer than the request deadline
+                let left: Duration = deadline::remaining(until).map_or(timeout, |r| r.min(timeout));
+                match Self::wait_or(&sender, left, tx.closed()).await {
+                    Ok(d) => permit.send(Ok(d.into())),
+                    Err(e) => {
+                        let idle: bool =
+                            matches!(e.code(), Code::DeadlineExceeded | Code::Cancelled);
+                        // a stream ends cleanly when idle
+                        if !(streaming && idle) {
+                            permit.send(Err(e));
                         }
                         return;
                     }
-                    Err(e) => match e.code() {
-                        Code::NotFound => continue,
-                        _ => {
-                            match tx.send(Err(e)).await {
-                                Ok(_) => {}
-                                Err(e) => {
-                                    log::warn!("Unable to send a reply: {e}");
-                                }
-                            }
-                            return;
-                        }
-                    },
                 }
             }
         });
 
         Ok(Response::new(ReceiverStream::new(rx)))
     }
+
+    async fn ack(&self, req: Request<AckRequest>) -> Result<Response<AckResponse>, Status> {
+        let ar: AckRequest = req.into_inner();
+        let checked: AckReq = ar.try_into()?;
+        Self::ack(&self.sender, checked.as_delivery_id()).await?;
+        Ok(Response::new(AckResponse {}))
+    }
+
+    async fn nack(&self, req: Request<NackRequest>) -> Result<Response<NackResponse>, Status> {
+        let nr: NackRequest = req.into_inner();
+        let checked: AckReq = nr.try_into()?;
+        Self::nack(&self.sender, checked.as_delivery_id()).await?;
+        Ok(Response::new(NackResponse {}))
+    }
+
+    async fn reject(
+        &self,
+        req: Request<RejectRequest>,
+    ) -> Result<Response<RejectResponse>, Status> {
+        let rr: RejectRequest = req.into_inner();
+        let checked: RejectReq = rr.try_into()?;
+        let delivery_id: Uuid = checked.as_ack().as_delivery_id();
+        Self::reject(&self.sender, delivery_id, checked.into_reason()).await?;
+        Ok(Response::new(RejectResponse {}))
+    }
+
+    async fn stats(&self, _req: Request<StatsRequest>) -> Result<Response<StatsResponse>, Status> {
+        let stats: ReqStats = Self::stats(&self.sender).await?;
+        Ok(Response::new(stats.into()))
+    }
 }
 
-async fn buf_svc_st_new(max_size: usize) -> BufSvcSt {
-    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+pub(crate) async fn buf_svc_st_new(
+    cfg: &ActorConfig,
+    max_size: usize,
+    visibility: Option<Duration>,
+    dead: Option<DeadLetter>,
+) -> BufSvcSt {
+    let (tx, mut rx) = cfg.channel();
+    let full: FullPolicy = cfg.as_full_policy();
     tokio::spawn(async move {
-        let mut vd: VecDeque<SaveInfo> = VecDeque::new();
+        let mut st: State = State::new(max_size, full, visibility, dead);
         loop {
-            match rx.recv().await {
-                None => return,
-                Some(req) => match req {
-                    Req::PushBack(si, reply) => {
-                        Req::handle_save(&mut vd, si, reply, max_size).await
-                    }
-                    Req::PopFront(reply) => Req::handle_get(&mut vd, reply).await,
+            let next: Option<Instant> = st.next_deadline();
+            let expire = async move {
+                match next {
+                    None => std::future::pending().await,
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                }
+            };
+            tokio::select! {
+                o = rx.recv() => match o {
+                    None => return,
+                    Some(req) => match req {
+                        Req::PushBack(si, reply) => Req::handle_save(&mut st, si, reply).await,
+                        Req::PushFront(v) => Req::handle_push_front(&mut st, v),
+                        Req::PopFront(reply) => Req::handle_get(&mut st, reply),
+                        Req::Wait(reply) => Req::handle_wait(&mut st, reply),
+                        Req::Unwait => Req::handle_unwait(&mut st),
+                        Req::Ack(id, reply) => Req::handle_ack(&mut st, id, reply).await,
+                        Req::Nack(id, reply) => Req::handle_nack(&mut st, id, reply).await,
+                        Req::Reject(id, reason, reply) => {
+                            Req::handle_reject(&mut st, id, reason, reply).await
+                        }
+                        Req::List(max_count, reply) => Req::handle_list(&st, max_count, reply).await,
+                        Req::Stats(reply) => Req::handle_stats(&st, reply).await,
+                        Req::Take(reply_id, reply) => {
+                            Req::handle_take(&mut st, reply_id, reply).await
+                        }
+                    },
                 },
+                _ = expire => {
+                    let now: Instant = Instant::now();
+                    Req::handle_parked(&mut st, now).await;
+                    Req::handle_expired(&mut st, now).await
+                }
             }
+            st.admit().await;
         }
     });
     BufSvcSt { sender: tx }
 }
 
+/// Creates a buffer which forgets loaded requests.
 pub async fn request_buffer_service_new(max_buf_size: usize) -> impl ReqBufferService {
-    buf_svc_st_new(max_buf_size).await
+    request_buffer_service_with_config(&ActorConfig::default(), max_buf_size).await
+}
+
+/// Creates a buffer which forgets loaded requests using the config.
+pub async fn request_buffer_service_with_config(
+    cfg: &ActorConfig,
+    max_buf_size: usize,
+) -> impl ReqBufferService {
+    buf_svc_st_new(cfg, max_buf_size, None, None).await
+}
+
+/// Creates a buffer which redelivers loaded requests not acked within the visibility timeout.
+pub async fn acked_request_buffer_service_new(
+    max_buf_size: usize,
+    visibility: Duration,
+) -> impl ReqBufferService {
+    acked_request_buffer_service_with_config(&ActorConfig::default(), max_buf_size, visibility)
+        .await
+}
+
+/// Creates a buffer which redelivers unacked requests using the config.
+pub async fn acked_request_buffer_service_with_config(
+    cfg: &ActorConfig,
+    max_buf_size: usize,
+    visibility: Duration,
+) -> impl ReqBufferService {
+    buf_svc_st_new(cfg, max_buf_size, Some(visibility), None).await
 }
@@ -0,0 +1,136 @@
+use std::time::SystemTime;
+
+use tonic::codec::Streaming;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status};
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::common::v1::Retry;
+
+use helper::proto::buffer::v1::req_buf::{AckRequest, NackRequest, RejectRequest};
+use helper::proto::buffer::v1::req_buf::{LoadRequest, LoadResponse};
+use helper::proto::buffer::v1::req_buf::{SaveRequest, SaveResponse};
+use helper::proto::buffer::v1::req_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::req_buffer_service_client::ReqBufferServiceClient;
+use helper::proto::direct::v1::conv_svc::ConvertRequest;
+
+#[derive(Clone)]
+pub struct ReqBufferClient {
+    inner: ReqBufferServiceClient<Channel>,
+}
+
+impl ReqBufferClient {
+    pub fn new(ch: Channel) -> Self {
+        Self {
+            inner: ReqBufferServiceClient::new(ch),
+        }
+    }
+
+    pub async fn connect<D>(dst: D) -> Result<Self, Status>
+    where
+        D: TryInto<Endpoint>,
+        D::Error: Into<tonic::codegen::StdError>,
+    {
+        let inner = ReqBufferServiceClient::connect(dst)
+            .await
+            .map_err(|e| Status::unavailable(format!("Unable to connect: {e}")))?;
+        Ok(Self { inner })
+    }
+
+    pub fn into_inner(self) -> ReqBufferServiceClient<Channel> {
+        self.inner
+    }
+
+    /// Saves the request; returns the saved time.
+    pub async fn save(
+        &mut self,
+        reply_id: Uuid,
+        req: ConvertRequest,
+        received: SystemTime,
+    ) -> Result<SystemTime, Status> {
+        let reqid: Uuid = Uuid::generate();
+        let sr = SaveRequest {
+            request_id: Some(reqid.into()),
+            reply_id: Some(reply_id.into()),
+            req: Some(req),
+            received: Some(received.into()),
+        };
+        let res: Response<SaveResponse> = self.inner.save(Request::new(sr)).await?;
+        let saved = res
+            .into_inner()
+            .saved
+            .ok_or_else(|| Status::internal(format!("saved time missing. request id: {reqid}")))?;
+        SystemTime::try_from(saved)
+            .map_err(|e| Status::internal(format!("invalid saved time: {e}")))
+    }
+
+    /// Loads a request using the retry.
+    pub async fn load<R>(&mut self, retry: R) -> Result<Streaming<LoadResponse>, Status>
+    where
+        R: Into<Retry>,
+    {
+        self.load_many(retry, 1, 1).await
+    }
+
+    /// Loads up to `max_count` requests using a stream with the `window`.
+    pub async fn load_many<R>(
+        &mut self,
+        retry: R,
+        max_count: u64,
+        window: u64,
+    ) -> Result<Streaming<LoadResponse>, Status>
+    where
+        R: Into<Retry>,
+    {
+        let reqid: Uuid = Uuid::generate();
+        let lr = LoadRequest {
+            request_id: Some(reqid.into()),
+            retry: Some(retry.into()),
+            max_count,
+            window,
+        };
+        let res: Response<_> = self.inner.load(Request::new(lr)).await?;
+        Ok(res.into_inner())
+    }
+
+    /// Acks the delivery; the request will not be redelivered.
+    pub async fn ack(&mut self, delivery_id: Uuid) -> Result<(), Status> {
+        let ar = AckRequest {
+            request_id: Some(Uuid::generate().into()),
+            delivery_id: Some(delivery_id.into()),
+        };
+        self.inner.ack(Request::new(ar)).await?;
+        Ok(())
+    }
+
+    /// Nacks the delivery; the request will be redelivered.
+    pub async fn nack(&mut self, delivery_id: Uuid) -> Result<(), Status> {
+        let nr = NackRequest {
+            request_id: Some(Uuid::generate().into()),
+            delivery_id: Some(delivery_id.into()),
+        };
+        self.inner.nack(Request::new(nr)).await?;
+        Ok(())
+    }
+
+    /// Rejects the delivery; the request will be moved to the dead letter buffer.
+    pub async fn reject(&mut self, delivery_id: Uuid, reason: String) -> Result<(), Status> {
+        let rr = RejectRequest {
+            request_id: Some(Uuid::generate().into()),
+            delivery_id: Some(delivery_id.into()),
+            reason,
+        };
+        self.inner.reject(Request::new(rr)).await?;
+        Ok(())
+    }
+
+    pub async fn stats(&mut self) -> Result<StatsResponse, Status> {
+        let sr = StatsRequest {
+            request_id: Some(Uuid::generate().into()),
+        };
+        let res: Response<StatsResponse> = self.inner.stats(Request::new(sr)).await?;
+        Ok(res.into_inner())
+    }
+}
@@ -0,0 +1,139 @@
+use core::time::Duration;
+use std::time::SystemTime;
+
+use prost_types::Timestamp;
+
+use tokio::time::Instant;
+
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status};
+
+use crate::retry::{deadline, exec};
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::common::v1::Retry;
+
+use helper::proto::buffer::v1::res_buf::{DelRequest, DelResponse};
+use helper::proto::buffer::v1::res_buf::{GetRequest, GetResponse};
+use helper::proto::buffer::v1::res_buf::{LenRequest, LenResponse};
+use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::res_buffer_service_client::ResBufferServiceClient;
+use helper::proto::direct::v1::conv_svc::ConvertResponse;
+
+#[derive(Clone)]
+pub struct ResBufferClient {
+    inner: ResBufferServiceClient<Channel>,
+}
+
+fn ts2time(t: Option<Timestamp>, name: &str) -> Result<SystemTime, Status> {
+    let t: Timestamp = t.ok_or_else(|| Status::internal(format!("{name} missing")))?;
+    SystemTime::try_from(t).map_err(|e| Status::internal(format!("invalid {name}: {e}")))
+}
+
+impl ResBufferClient {
+    pub fn new(ch: Channel) -> Self {
+        Self {
+            inner: ResBufferServiceClient::new(ch),
+        }
+    }
+
+    pub async fn connect<D>(dst: D) -> Result<Self, Status>
+    where
+        D: TryInto<Endpoint>,
+        D::Error: Into<tonic::codegen::StdError>,
+    {
+        let inner = ResBufferServiceClient::connect(dst)
+            .await
+            .map_err(|e| Status::unavailable(format!("Unable to connect: {e}")))?;
+        Ok(Self { inner })
+    }
+
+    pub fn into_inner(self) -> ResBufferServiceClient<Channel> {
+        self.inner
+    }
+
+    /// Sets the response for the reply id; returns the set time.
+    pub async fn set(
+        &mut self,
+        reply_id: Uuid,
+        res: ConvertResponse,
+        received: Timestamp,
+        saved: Timestamp,
+        loaded: Option<Timestamp>,
+    ) -> Result<SystemTime, Status> {
+        let converted: Timestamp = res
+            .converted
+            .clone()
+            .unwrap_or_else(|| SystemTime::now().into());
+        let sr = SetRequest {
+            request_id: Some(Uuid::generate().into()),
+            reply_id: Some(reply_id.into()),
+            res: Some(res),
+            received: Some(received),
+            saved: Some(saved),
+            converted: Some(converted),
+            loaded,
+        };
+        let res: Response<SetResponse> = self.inner.set(Request::new(sr)).await?;
+        ts2time(res.into_inner().set, "set time")
+    }
+
+    /// Waits for the response of the reply id; retried using the retry while not yet set(up to
+    /// the timeout of the retry in total).
+    pub async fn get<R>(&mut self, reply_id: Uuid, retry: R) -> Result<GetResponse, Status>
+    where
+        R: Into<Retry>,
+    {
+        let retry: Retry = retry.into();
+        let policy: crate::retry::Retry = (&retry).into();
+        let until: Option<Instant> = Some(deadline::overall(policy.as_timeout(), None));
+        exec::run_until(&policy, until, exec::is_transient, |_| {
+            let mut inner: ResBufferServiceClient<Channel> = self.inner.clone();
+            let left: Option<Duration> = deadline::remaining(until);
+            let gr = GetRequest {
+                request_id: Some(Uuid::generate().into()),
+                reply_id: Some(reply_id.into()),
+                retry: Some((&policy.within(left)).into()),
+            };
+            async move {
+                let res: Response<_> = inner.get(Request::new(gr)).await?;
+                let mut gs = res.into_inner();
+                let o: Option<GetResponse> = gs.message().await?;
+                o.ok_or_else(|| Status::not_found(format!("no response got. reply id: {reply_id}")))
+            }
+        })
+        .await
+    }
+
+    /// Removes the response of the reply id; returns the removed time.
+    pub async fn del(&mut self, reply_id: Uuid) -> Result<SystemTime, Status> {
+        let dr = DelRequest {
+            request_id: Some(Uuid::generate().into()),
+            reply_id: Some(reply_id.into()),
+        };
+        let res: Response<DelResponse> = self.inner.del(Request::new(dr)).await?;
+        ts2time(res.into_inner().removed, "removed time")
+    }
+
+    pub async fn len(&mut self) -> Result<u64, Status> {
+        let lr = LenRequest {
+            request_id: Some(Uuid::generate().into()),
+        };
+        let res: Response<LenResponse> = self.inner.len(Request::new(lr)).await?;
+        Ok(res.into_inner().length)
+    }
+
+    pub async fn is_empty(&mut self) -> Result<bool, Status> {
+        self.len().await.map(|l: u64| 0 == l)
+    }
+
+    pub async fn stats(&mut self) -> Result<StatsResponse, Status> {
+        let sr = StatsRequest {
+            request_id: Some(Uuid::generate().into()),
+        };
+        let res: Response<StatsResponse> = self.inner.stats(Request::new(sr)).await?;
+        Ok(res.into_inner())
+    }
+}
@@ -0,0 +1,67 @@
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status};
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use helper::proto::direct::v1::convert_service_client::ConvertServiceClient;
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+
+#[derive(Clone)]
+pub struct ConvertClient {
+    inner: ConvertServiceClient<Channel>,
+}
+
+impl ConvertClient {
+    pub fn new(ch: Channel) -> Self {
+        Self {
+            inner: ConvertServiceClient::new(ch),
+        }
+    }
+
+    pub async fn connect<D>(dst: D) -> Result<Self, Status>
+    where
+        D: TryInto<Endpoint>,
+        D::Error: Into<tonic::codegen::StdError>,
+    {
+        let inner = ConvertServiceClient::connect(dst)
+            .await
+            .map_err(|e| Status::unavailable(format!("Unable to connect: {e}")))?;
+        Ok(Self { inner })
+    }
+
+    pub fn into_inner(self) -> ConvertServiceClient<Channel> {
+        self.inner
+    }
+
+    /// Converts the seed using a new request id.
+    pub async fn convert(&mut self, seed: Vec<u8>) -> Result<ConvertResponse, Status> {
+        let reqid: Uuid = Uuid::generate();
+        let req = ConvertRequest {
+            request_id: Some(reqid.into()),
+            seed,
+        };
+        self.convert_raw(Request::new(req)).await
+    }
+
+    pub async fn convert_raw(
+        &mut self,
+        req: Request<ConvertRequest>,
+    ) -> Result<ConvertResponse, Status> {
+        let res: Response<ConvertResponse> = self.inner.convert(req).await?;
+        Ok(res.into_inner())
+    }
+}
+
+/// Sends the requests to the remote service(e.g, to be wrapped by a [`crate::retry::layer`]).
+#[tonic::async_trait]
+impl ConvertService for ConvertClient {
+    async fn convert(
+        &self,
+        req: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        let mut inner: ConvertServiceClient<Channel> = self.inner.clone();
+        inner.convert(req).await
+    }
+}
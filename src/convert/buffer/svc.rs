@@ -1,12 +1,20 @@
+use core::time::Duration;
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use tokio::time::Instant;
+
 use futures::StreamExt;
 
 use tonic::{Request, Response, Status};
 
+use crate::actor::ActorConfig;
+use crate::retry::{deadline, exec};
 use crate::uuid::Uuid;
 
+use crate::buffer::res::btree::svc::BufSvcSt as ResBufSvc;
+use crate::buffer::vecdeque::svc::BufSvcSt as ReqBufSvc;
+
 use crate::rpc::perf::helper;
 
 use helper::proto::common::v1::Retry;
@@ -26,6 +34,79 @@ pub struct Buffered<Q, S> {
     retry: Retry,
 }
 
+impl<Q, S> Buffered<Q, S> {
+    pub fn builder() -> BufferedBuilder<Q, S> {
+        BufferedBuilder::default()
+    }
+
+    pub fn as_req_svc(&self) -> &Arc<Q> {
+        &self.req_svc
+    }
+
+    pub fn as_res_svc(&self) -> &Arc<S> {
+        &self.res_svc
+    }
+
+    pub fn as_retry(&self) -> &Retry {
+        &self.retry
+    }
+}
+
+pub struct BufferedBuilder<Q, S> {
+    req_svc: Option<Arc<Q>>,
+    res_svc: Option<Arc<S>>,
+
+    retry: Option<Retry>,
+}
+
+impl<Q, S> Default for BufferedBuilder<Q, S> {
+    fn default() -> Self {
+        Self {
+            req_svc: None,
+            res_svc: None,
+            retry: None,
+        }
+    }
+}
+
+impl<Q, S> BufferedBuilder<Q, S> {
+    pub fn req_svc(mut self, req_svc: Arc<Q>) -> Self {
+        self.req_svc = Some(req_svc);
+        self
+    }
+
+    pub fn res_svc(mut self, res_svc: Arc<S>) -> Self {
+        self.res_svc = Some(res_svc);
+        self
+    }
+
+    /// Sets the retry used to get a response from the response buffer.
+    pub fn retry<R>(mut self, retry: R) -> Self
+    where
+        R: Into<Retry>,
+    {
+        self.retry = Some(retry.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Buffered<Q, S>, Status> {
+        let req_svc: Arc<Q> = self
+            .req_svc
+            .ok_or_else(|| Status::invalid_argument("request buffer missing"))?;
+        let res_svc: Arc<S> = self
+            .res_svc
+            .ok_or_else(|| Status::invalid_argument("response buffer missing"))?;
+        let retry: Retry = self
+            .retry
+            .ok_or_else(|| Status::invalid_argument("retry missing"))?;
+        Ok(Buffered {
+            req_svc,
+            res_svc,
+            retry,
+        })
+    }
+}
+
 impl<Q, S> Buffered<Q, S>
 where
     Q: ReqBufferService,
@@ -35,8 +116,9 @@ where
         received: SystemTime,
         req: ConvertRequest,
         reply: Uuid,
+        timeout: Option<Duration>,
     ) -> Result<Response<SaveResponse>, Status> {
-        let reqid: Uuid = Uuid::new_v4();
+        let reqid: Uuid = Uuid::generate();
 
         let saveq = SaveRequest {
             request_id: Some(reqid.into()),
@@ -44,7 +126,7 @@ where
             req: Some(req),
             received: Some(received.into()),
         };
-        self.req_svc.save(Request::new(saveq)).await
+        self.req_svc.save(with_timeout(saveq, timeout)).await
     }
 }
 
@@ -52,14 +134,19 @@ impl<Q, S> Buffered<Q, S>
 where
     S: Send + Sync + 'static + ResBufferService,
 {
-    async fn get(&self, reply: Uuid, retry: Retry) -> Result<Response<S::GetStream>, Status> {
-        let reqid: Uuid = Uuid::new_v4();
+    async fn get(
+        &self,
+        reply: Uuid,
+        retry: Retry,
+        timeout: Option<Duration>,
+    ) -> Result<Response<S::GetStream>, Status> {
+        let reqid: Uuid = Uuid::generate();
         let req = GetRequest {
             request_id: Some(reqid.into()),
             reply_id: Some(reply.into()),
             retry: Some(retry),
         };
-        self.res_svc.get(Request::new(req)).await
+        self.res_svc.get(with_timeout(req, timeout)).await
     }
 }
 
@@ -75,16 +162,78 @@ where
         req: Request<ConvertRequest>,
     ) -> Result<Response<ConvertResponse>, Status> {
         let received: SystemTime = SystemTime::now();
+        let deadline: Option<Duration> = deadline::from_metadata(req.metadata());
+        let until: Option<Instant> = deadline.map(|d: Duration| Instant::now() + d);
         let cr: ConvertRequest = req.into_inner();
-        let reply: Uuid = Uuid::new_v4();
-
-        self.save(received, cr, reply).await?;
-        let got: Response<_> = self.get(reply, self.retry.clone()).await?;
-        let mut gs: S::GetStream = got.into_inner();
-        let ro: Option<_> = gs.next().await;
-        let res: Result<_, _> = ro.ok_or_else(|| Status::internal("No reply from upstream"))?;
-        let gr: GetResponse = res?;
+        let reply: Uuid = Uuid::generate();
+        let retry: crate::retry::Retry = crate::retry::Retry::from(&self.retry).within(deadline);
+        // the timeout bounds all the attempts(not each one)
+        let until: Option<Instant> = Some(deadline::overall(retry.as_timeout(), until));
+
+        // the save and the get are retried as a single request
+        let scope: exec::Scope = exec::Scope::shared();
+        // the downstream calls get the time left until the deadline
+        scope
+            .run_until(&retry, until, exec::is_transient, |_| {
+                self.save(received, cr.clone(), reply, deadline::remaining(until))
+            })
+            .await?;
+        let gr: GetResponse = scope
+            .run_until(&retry, until, exec::is_transient, |_| async {
+                let left: Option<Duration> = deadline::remaining(until);
+                let bounded: Retry = (&retry.within(left)).into();
+                let got: Response<_> = self.get(reply, bounded, left).await?;
+                let mut gs: S::GetStream = got.into_inner();
+                let ro: Option<_> = gs.next().await;
+                ro.ok_or_else(|| Status::internal("No reply from upstream"))?
+            })
+            .await?;
         let reply: ConvertResponse = gr.res.ok_or_else(|| Status::internal("empty response"))?;
         Ok(Response::new(reply))
     }
 }
+
+/// Creates a request with the `grpc-timeout` header(if any).
+fn with_timeout<T>(msg: T, timeout: Option<Duration>) -> Request<T> {
+    let mut req: Request<T> = Request::new(msg);
+    if let Some(t) = timeout {
+        req.set_timeout(t);
+    }
+    req
+}
+
+/// Creates a [`ConvertService`] which saves requests to an in-process request buffer
+/// and waits for the responses in an in-process response buffer.
+///
+/// The buffers can be shared with workers(e.g, GetConvReqService, IndirectService)
+/// using [`Buffered::as_req_svc`] and [`Buffered::as_res_svc`].
+pub async fn buffered_convert_service_new<R>(
+    max_req: usize,
+    max_res: usize,
+    retry: R,
+) -> Buffered<ReqBufSvc, ResBufSvc>
+where
+    R: Into<Retry>,
+{
+    buffered_convert_service_with_config(&ActorConfig::default(), max_req, max_res, retry).await
+}
+
+/// Creates a [`buffered_convert_service_new`] whose buffers use the config.
+pub async fn buffered_convert_service_with_config<R>(
+    cfg: &ActorConfig,
+    max_req: usize,
+    max_res: usize,
+    retry: R,
+) -> Buffered<ReqBufSvc, ResBufSvc>
+where
+    R: Into<Retry>,
+{
+    let req_svc: ReqBufSvc =
+        crate::buffer::vecdeque::svc::buf_svc_st_new(cfg, max_req, None, None).await;
+    let res_svc: ResBufSvc = crate::buffer::res::btree::svc::buf_svc_st_new(cfg, max_res).await;
+    Buffered {
+        req_svc: Arc::new(req_svc),
+        res_svc: Arc::new(res_svc),
+        retry: retry.into(),
+    }
+}
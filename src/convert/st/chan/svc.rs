@@ -3,6 +3,8 @@ use tokio::sync::mpsc::{Receiver, Sender};
 
 use tonic::{Request, Response, Status};
 
+use crate::actor::ActorConfig;
+
 use crate::rpc::perf::helper;
 use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
 use helper::proto::direct::v1::convert_service_server::ConvertService;
@@ -81,7 +83,14 @@ pub fn conv_svc_new<G>(conv_svc_mut: G) -> impl ConvertService
 where
     G: ConvertServiceMut + Send + 'static,
 {
-    let (tx, rx) = mpsc::channel(1);
+    conv_svc_with_config(&ActorConfig::default(), conv_svc_mut)
+}
+
+pub fn conv_svc_with_config<G>(cfg: &ActorConfig, conv_svc_mut: G) -> impl ConvertService
+where
+    G: ConvertServiceMut + Send + 'static,
+{
+    let (tx, rx) = cfg.channel();
     let mut cloop = ConvLoop {
         conv_svc_mut,
         requests: rx,
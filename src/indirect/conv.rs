@@ -1 +1,2 @@
+pub mod evt;
 pub mod req;
@@ -0,0 +1,86 @@
+use std::time::SystemTime;
+
+use prost_types::Timestamp;
+
+use tonic::Status;
+
+use crate::uuid::Uuid;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::SetRequest;
+use helper::proto::direct::v1::conv_svc::ConvertResponse;
+use helper::proto::indirect::v1::conv_evt::ConvertedRequest;
+
+pub struct ConvertedReq {
+    reply_id: Uuid,
+    response: ConvertResponse,
+    received: Option<Timestamp>,
+    saved: Option<Timestamp>,
+    converted: Timestamp,
+    loaded: Option<Timestamp>,
+    delivery_id: Option<Uuid>,
+}
+
+impl ConvertedReq {
+    pub fn as_reply_id(&self) -> Uuid {
+        self.reply_id
+    }
+
+    pub fn as_received(&self) -> Option<&Timestamp> {
+        self.received.as_ref()
+    }
+    pub fn as_saved(&self) -> Option<&Timestamp> {
+        self.saved.as_ref()
+    }
+    pub fn as_converted(&self) -> &Timestamp {
+        &self.converted
+    }
+    pub fn as_loaded(&self) -> Option<&Timestamp> {
+        self.loaded.as_ref()
+    }
+
+    /// The delivery to be acked once the response is saved(if any).
+    pub fn as_delivery_id(&self) -> Option<Uuid> {
+        self.delivery_id
+    }
+
+    pub fn into_set_request(self, request_id: Uuid) -> SetRequest {
+        SetRequest {
+            request_id: Some(request_id.into()),
+            reply_id: Some(self.reply_id.into()),
+            res: Some(self.response),
+            received: self.received,
+            saved: self.saved,
+            converted: Some(self.converted),
+            loaded: self.loaded,
+        }
+    }
+}
+
+impl TryFrom<ConvertedRequest> for ConvertedReq {
+    type Error = Status;
+    fn try_from(g: ConvertedRequest) -> Result<Self, Self::Error> {
+        let reply_id: Uuid = g
+            .reply_id
+            .as_ref()
+            .try_into()
+            .map_err(|_| Status::invalid_argument("reply id missing"))?;
+        let response: ConvertResponse = g.res.ok_or_else(|| {
+            Status::invalid_argument(format!("response missing. reply id: {reply_id}"))
+        })?;
+        let converted: Timestamp = response
+            .converted
+            .clone()
+            .unwrap_or_else(|| SystemTime::now().into());
+        Ok(Self {
+            reply_id,
+            response,
+            // missing if sent by workers older than the timestamps
+            received: g.received,
+            saved: g.saved,
+            converted,
+            loaded: g.loaded,
+            delivery_id: g.delivery_id.as_ref().map(Uuid::from),
+        })
+    }
+}
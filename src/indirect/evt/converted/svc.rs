@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use tonic::{Code, Request, Response, Status};
+
+use crate::uuid::Uuid;
+
+use crate::indirect::conv::evt::converted::req::ConvertedReq;
+
+use crate::rpc::perf::helper;
+
+use helper::proto::buffer::v1::req_buf::AckRequest;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buf::{SetRequest, SetResponse};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+
+use helper::proto::indirect::v1::conv_evt::{ConvertedRequest, ConvertedResponse};
+use helper::proto::indirect::v1::indirect_service_server::IndirectService;
+
+/// Saves converted responses(sent by workers) to the response buffer.
+///
+/// Responses to delivered requests(with delivery ids) are rejected(`FailedPrecondition`):
+/// nothing would ack them; use [`Acked`] instead.
+pub struct Buffered<S> {
+    res_svc: Arc<S>,
+}
+
+impl<S> Buffered<S>
+where
+    S: ResBufferService,
+{
+    pub fn new(res_svc: Arc<S>) -> Self {
+        Self { res_svc }
+    }
+
+    pub async fn set(res_svc: &S, req: SetRequest) -> Result<SetResponse, Status> {
+        let res: Response<SetResponse> = res_svc.set(Request::new(req)).await?;
+        Ok(res.into_inner())
+    }
+
+    /// Saves the response; returns the delivery id to be acked(if any).
+    pub async fn save(
+        res_svc: &S,
+        checked: ConvertedReq,
+    ) -> Result<(Option<Uuid>, SetResponse), Status> {
+        let delivery_id: Option<Uuid> = checked.as_delivery_id();
+        let reqid: Uuid = Uuid::generate();
+        let sr: SetRequest = checked.into_set_request(reqid);
+        let set: SetResponse = Self::set(res_svc, sr).await?;
+        Ok((delivery_id, set))
+    }
+}
+
+#[tonic::async_trait]
+impl<S> IndirectService for Buffered<S>
+where
+    S: Send + Sync + 'static + ResBufferService,
+{
+    async fn converted(
+        &self,
+        req: Request<ConvertedRequest>,
+    ) -> Result<Response<ConvertedResponse>, Status> {
+        let cr: ConvertedRequest = req.into_inner();
+        let checked: ConvertedReq = cr.try_into()?;
+        if let Some(delivery_id) = checked.as_delivery_id() {
+            return Err(Status::failed_precondition(format!(
+                "acks not supported. delivery id: {delivery_id}"
+            )));
+        }
+        let (_, set) = Self::save(&self.res_svc, checked).await?;
+        let reply = ConvertedResponse { sent: set.set };
+        Ok(Response::new(reply))
+    }
+}
+
+/// Saves converted responses to the response buffer and acks their deliveries.
+pub struct Acked<S, Q> {
+    res_svc: Arc<S>,
+    req_svc: Arc<Q>,
+}
+
+impl<S, Q> Acked<S, Q>
+where
+    Q: ReqBufferService,
+{
+    pub fn new(res_svc: Arc<S>, req_svc: Arc<Q>) -> Self {
+        Self { res_svc, req_svc }
+    }
+
+    pub async fn ack(req_svc: &Q, delivery_id: Uuid) -> Result<(), Status> {
+        let ar = AckRequest {
+            request_id: Some(Uuid::generate().into()),
+            delivery_id: Some(delivery_id.into()),
+        };
+        req_svc.ack(Request::new(ar)).await?;
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl<S, Q> IndirectService for Acked<S, Q>
+where
+    S: Send + Sync + 'static + ResBufferService,
+    Q: Send + Sync + 'static + ReqBufferService,
+{
+    async fn converted(
+        &self,
+        req: Request<ConvertedRequest>,
+    ) -> Result<Response<ConvertedResponse>, Status> {
+        let cr: ConvertedRequest = req.into_inner();
+        let checked: ConvertedReq = cr.try_into()?;
+        let delivery_id: Option<Uuid> = checked.as_delivery_id();
+        let saved: Result<_, Status> = Buffered::save(self.res_svc.as_ref(), checked).await;
+        // a redelivered request may have been saved already
+        let done: bool = match &saved {
+            Ok(_) => true,
+            Err(e) => e.code() == Code::AlreadyExists,
+        };
+        if let Some(delivery_id) = delivery_id.filter(|_| done) {
+            Self::ack(&self.req_svc, delivery_id).await?;
+        }
+        let (_, set) = saved?;
+        let reply = ConvertedResponse { sent: set.set };
+        Ok(Response::new(reply))
+    }
+}
+
+pub fn indirect_service_new<S>(res_svc: Arc<S>) -> impl IndirectService
+where
+    S: Send + Sync + 'static + ResBufferService,
+{
+    Buffered::new(res_svc)
+}
+
+/// Creates a service which acks the deliveries of the saved responses.
+pub fn acked_indirect_service_new<S, Q>(res_svc: Arc<S>, req_svc: Arc<Q>) -> impl IndirectService
+where
+    S: Send + Sync + 'static + ResBufferService,
+    Q: Send + Sync + 'static + ReqBufferService,
+{
+    Acked::new(res_svc, req_svc)
+}
@@ -1,3 +1,4 @@
+use core::time::Duration;
 use std::sync::Arc;
 
 use futures::StreamExt;
@@ -6,6 +7,9 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use tonic::{Request, Response, Status};
 
+use tokio::time::Instant;
+
+use crate::retry::{deadline, exec};
 use crate::uuid::Uuid;
 
 use crate::indirect::conv::req::get::req::GetReq;
@@ -29,6 +33,61 @@ pub struct Buffered<Q> {
     retry: Retry,
 }
 
+impl<Q> Buffered<Q> {
+    pub fn builder() -> BufferedBuilder<Q> {
+        BufferedBuilder::default()
+    }
+
+    pub fn as_req_svc(&self) -> &Arc<Q> {
+        &self.req_svc
+    }
+
+    pub fn as_retry(&self) -> &Retry {
+        &self.retry
+    }
+}
+
+pub struct BufferedBuilder<Q> {
+    req_svc: Option<Arc<Q>>,
+
+    retry: Option<Retry>,
+}
+
+impl<Q> Default for BufferedBuilder<Q> {
+    fn default() -> Self {
+        Self {
+            req_svc: None,
+            retry: None,
+        }
+    }
+}
+
+impl<Q> BufferedBuilder<Q> {
+    pub fn req_svc(mut self, req_svc: Arc<Q>) -> Self {
+        self.req_svc = Some(req_svc);
+        self
+    }
+
+    /// Sets the retry used to load a request from the request buffer.
+    pub fn retry<R>(mut self, retry: R) -> Self
+    where
+        R: Into<Retry>,
+    {
+        self.retry = Some(retry.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Buffered<Q>, Status> {
+        let req_svc: Arc<Q> = self
+            .req_svc
+            .ok_or_else(|| Status::invalid_argument("request buffer missing"))?;
+        let retry: Retry = self
+            .retry
+            .ok_or_else(|| Status::invalid_argument("retry missing"))?;
+        Ok(Buffered { req_svc, retry })
+    }
+}
+
 impl<Q> Buffered<Q>
 where
     Q: ReqBufferService,
@@ -38,16 +97,28 @@ where
         Ok(res.into_inner())
     }
 
+    /// Loads a request; retried using the retry while no request is saved(up to the timeout
+    /// of the retry in total).
     pub async fn load_retry(
         req_svc: &Q,
         reqid: Uuid,
         retry: Retry,
-    ) -> Result<Q::LoadStream, Status> {
-        let req = LoadRequest {
-            request_id: Some(reqid.into()),
-            retry: Some(retry),
-        };
-        Self::load(req_svc, req).await
+    ) -> Result<LoadResponse, Status> {
+        let policy: crate::retry::Retry = (&retry).into();
+        let until: Option<Instant> = Some(deadline::overall(policy.as_timeout(), None));
+        exec::run_until(&policy, until, exec::is_transient, |_| async {
+            let left: Option<Duration> = deadline::remaining(until);
+            let req = LoadRequest {
+                request_id: Some(reqid.into()),
+                retry: Some((&policy.within(left)).into()),
+                max_count: 1,
+                window: 1,
+            };
+            let ls: Q::LoadStream = Self::load(req_svc, req).await?;
+            let o: Option<Result<LoadResponse, Status>> = Box::pin(ls).next().await;
+            o.ok_or_else(|| Status::deadline_exceeded(format!("no request. request id: {reqid}")))?
+        })
+        .await
     }
 }
 
@@ -67,33 +138,24 @@ where
 
         let req_svc: Arc<Q> = self.req_svc.clone();
         tokio::spawn(async move {
-            match Self::load_retry(&req_svc, reqid, retry)
+            let r: Result<GetResponse, Status> = Self::load_retry(&req_svc, reqid, retry)
                 .await
-                .map(|ls: Q::LoadStream| {
-                    ls.map(|r: Result<LoadResponse, _>| {
-                        r.map(|lr: LoadResponse| {
-                            let req: Option<ConvertRequest> = lr.req;
-                            let reply_id: Option<_> = lr.reply_id;
-                            GetResponse { req, reply_id }
-                        })
-                    })
-                    .all(|r: Result<GetResponse, Status>| async {
-                        match tx.send(r).await {
-                            Ok(_) => true,
-                            Err(e) => {
-                                log::warn!("Unable to send a request(GetResponse): {e}");
-                                false
-                            }
-                        }
-                    })
-                }) {
-                Ok(all) => {
-                    all.await;
-                }
-                Err(e) => match tx.send(Err(e)).await {
-                    Ok(_) => {}
-                    Err(se) => log::warn!("Unable to send an error: {se}"),
-                },
+                .map(|lr: LoadResponse| {
+                    let req: Option<ConvertRequest> = lr.req;
+                    let reply_id: Option<_> = lr.reply_id;
+                    GetResponse {
+                        req,
+                        reply_id,
+                        received: lr.received,
+                        saved: lr.saved,
+                        loaded: lr.loaded,
+                        delivery_id: lr.delivery_id,
+                        delivery_count: lr.delivery_count,
+                    }
+                });
+            match tx.send(r).await {
+                Ok(_) => {}
+                Err(e) => log::warn!("Unable to send a request(GetResponse): {e}"),
             }
         });
         Ok(Response::new(ReceiverStream::new(rx)))
@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 pub use log;
 pub use tonic;
 
@@ -38,9 +40,19 @@ pub mod uuid;
 
 pub mod retry;
 
+pub mod actor;
+
 pub mod convert;
 
 pub mod direct;
 pub mod indirect;
 
 pub mod buffer;
+
+pub mod stats;
+
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(feature = "loadgen")]
+pub mod loadgen;
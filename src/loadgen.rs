@@ -0,0 +1,4 @@
+pub mod closed;
+pub mod open;
+pub mod report;
+pub mod seed;
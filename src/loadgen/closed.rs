@@ -0,0 +1,93 @@
+use core::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use tonic::{Code, Status};
+
+use crate::client::convert::ConvertClient;
+
+use crate::loadgen::report::{Report, Sample};
+use crate::loadgen::seed::Seed;
+
+/// When to stop sending requests.
+#[derive(Clone, Copy)]
+pub enum Limit {
+    Requests(u64),
+    Duration(Duration),
+}
+
+/// Closed-loop load: each worker sends the next request after the previous one completes.
+#[derive(Clone)]
+pub struct Config {
+    concurrency: usize,
+    limit: Limit,
+    seed: Seed,
+}
+
+impl Config {
+    pub fn new(concurrency: usize, limit: Limit, seed: Seed) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            limit,
+            seed,
+        }
+    }
+
+    pub fn as_concurrency(&self) -> usize {
+        self.concurrency
+    }
+    pub fn as_limit(&self) -> Limit {
+        self.limit
+    }
+    pub fn as_seed(&self) -> &Seed {
+        &self.seed
+    }
+}
+
+fn done(limit: Limit, issued: &AtomicU64, started: Instant) -> bool {
+    match limit {
+        Limit::Requests(n) => n <= issued.fetch_add(1, Ordering::Relaxed),
+        Limit::Duration(d) => d <= started.elapsed(),
+    }
+}
+
+async fn work(
+    mut client: ConvertClient,
+    cfg: Config,
+    issued: Arc<AtomicU64>,
+    started: Instant,
+) -> Vec<Sample> {
+    let mut samples: Vec<Sample> = vec![];
+    while !done(cfg.limit, &issued, started) {
+        let seed: Vec<u8> = cfg.seed.generate();
+        let sent: Instant = Instant::now();
+        let code: Code = match client.convert(seed).await {
+            Ok(_) => Code::Ok,
+            Err(e) => e.code(),
+        };
+        let latency: Duration = sent.elapsed();
+        samples.push(Sample::new(sent - started, latency, code));
+    }
+    samples
+}
+
+/// Sends requests using `concurrency` workers until the limit is reached.
+pub async fn run(client: ConvertClient, cfg: &Config) -> Result<Report, Status> {
+    let issued: Arc<AtomicU64> = Arc::new(AtomicU64::new(0));
+    let started: Instant = Instant::now();
+    let workers: Vec<_> = (0..cfg.concurrency)
+        .map(|_| {
+            let c: ConvertClient = client.clone();
+            tokio::spawn(work(c, cfg.clone(), issued.clone(), started))
+        })
+        .collect();
+    let mut samples: Vec<Sample> = vec![];
+    for w in workers {
+        let s: Vec<Sample> = w
+            .await
+            .map_err(|e| Status::internal(format!("Unable to join a worker: {e}")))?;
+        samples.extend(s);
+    }
+    Report::new(samples, started.elapsed())
+}
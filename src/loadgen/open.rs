@@ -0,0 +1,97 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use tokio::task::JoinHandle;
+
+use tonic::{Code, Status};
+
+use crate::client::convert::ConvertClient;
+
+use crate::loadgen::closed::Limit;
+use crate::loadgen::report::{Report, Sample};
+use crate::loadgen::seed::Seed;
+
+/// Open-loop load: requests are sent at a constant rate regardless of the responses.
+///
+/// Latencies are measured from the intended send time, not from the actual send time,
+/// so a stalled server can not hide the delays of requests which should have been sent
+/// during the stall(coordinated omission).
+#[derive(Clone)]
+pub struct Config {
+    rate: f64,
+    limit: Limit,
+    seed: Seed,
+}
+
+impl Config {
+    /// Creates a config; `rate` is requests per second.
+    pub fn new(rate: f64, limit: Limit, seed: Seed) -> Result<Self, Status> {
+        let valid: bool = rate.is_finite() && 0.0 < rate;
+        valid
+            .then_some(())
+            .ok_or_else(|| Status::invalid_argument(format!("invalid rate: {rate}")))?;
+        Ok(Self { rate, limit, seed })
+    }
+
+    pub fn as_rate(&self) -> f64 {
+        self.rate
+    }
+    pub fn as_limit(&self) -> Limit {
+        self.limit
+    }
+    pub fn as_seed(&self) -> &Seed {
+        &self.seed
+    }
+
+    /// Intended send time of the i-th request relative to the start.
+    pub fn intended(&self, i: u64) -> Duration {
+        Duration::from_secs_f64((i as f64) / self.rate)
+    }
+
+    fn in_limit(&self, i: u64, offset: Duration) -> bool {
+        match self.limit {
+            Limit::Requests(n) => i < n,
+            Limit::Duration(d) => offset < d,
+        }
+    }
+}
+
+async fn send(
+    mut client: ConvertClient,
+    seed: Vec<u8>,
+    intended: Instant,
+    offset: Duration,
+) -> Sample {
+    let code: Code = match client.convert(seed).await {
+        Ok(_) => Code::Ok,
+        Err(e) => e.code(),
+    };
+    let latency: Duration = Instant::now().saturating_duration_since(intended);
+    Sample::new(offset, latency, code)
+}
+
+/// Sends requests following the schedule of intended send times until the limit is reached.
+pub async fn run(client: ConvertClient, cfg: &Config) -> Result<Report, Status> {
+    let started: Instant = Instant::now();
+    let mut sent: Vec<JoinHandle<Sample>> = vec![];
+    let mut i: u64 = 0;
+    loop {
+        let offset: Duration = cfg.intended(i);
+        if !cfg.in_limit(i, offset) {
+            break;
+        }
+        let intended: Instant = started + offset;
+        tokio::time::sleep_until(intended.into()).await;
+        let seed: Vec<u8> = cfg.seed.generate();
+        sent.push(tokio::spawn(send(client.clone(), seed, intended, offset)));
+        i += 1;
+    }
+    let mut samples: Vec<Sample> = Vec::with_capacity(sent.len());
+    for s in sent {
+        let sample: Sample = s
+            .await
+            .map_err(|e| Status::internal(format!("Unable to join a request: {e}")))?;
+        samples.push(sample);
+    }
+    Report::new(samples, started.elapsed())
+}
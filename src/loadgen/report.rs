@@ -0,0 +1,136 @@
+use core::fmt;
+use core::time::Duration;
+
+use tonic::{Code, Status};
+
+use crate::stats::hist::LatencyHist;
+
+/// Result of a single request.
+#[derive(Clone, Copy)]
+pub struct Sample {
+    offset: Duration,
+    latency: Duration,
+    code: Code,
+}
+
+impl Sample {
+    pub fn new(offset: Duration, latency: Duration, code: Code) -> Self {
+        Self {
+            offset,
+            latency,
+            code,
+        }
+    }
+
+    /// Send time relative to the start of the run.
+    pub fn as_offset(&self) -> Duration {
+        self.offset
+    }
+    pub fn as_latency(&self) -> Duration {
+        self.latency
+    }
+    pub fn as_code(&self) -> Code {
+        self.code
+    }
+    pub fn is_ok(&self) -> bool {
+        Code::Ok == self.code
+    }
+}
+
+pub struct Report {
+    samples: Vec<Sample>,
+    elapsed: Duration,
+    ok: LatencyHist,
+    err: LatencyHist,
+}
+
+impl Report {
+    pub fn new(mut samples: Vec<Sample>, elapsed: Duration) -> Result<Self, Status> {
+        samples.sort_by_key(|s: &Sample| s.offset);
+        let mut ok: LatencyHist = LatencyHist::new()?;
+        let mut err: LatencyHist = LatencyHist::new()?;
+        for s in &samples {
+            match s.is_ok() {
+                true => ok.record(s.latency),
+                false => err.record(s.latency),
+            }
+        }
+        Ok(Self {
+            samples,
+            elapsed,
+            ok,
+            err,
+        })
+    }
+
+    pub fn as_samples(&self) -> &[Sample] {
+        &self.samples
+    }
+    pub fn as_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn ok_count(&self) -> u64 {
+        self.samples.iter().filter(|s| s.is_ok()).count() as u64
+    }
+    pub fn err_count(&self) -> u64 {
+        self.samples.iter().filter(|s| !s.is_ok()).count() as u64
+    }
+
+    /// Successful requests per second.
+    pub fn throughput(&self) -> f64 {
+        let secs: f64 = self.elapsed.as_secs_f64();
+        match 0.0 < secs {
+            true => (self.ok_count() as f64) / secs,
+            false => 0.0,
+        }
+    }
+
+    fn at(h: &LatencyHist, q: f64) -> Option<Duration> {
+        (!h.is_empty()).then(|| h.quantile(q.clamp(0.0, 1.0)))
+    }
+
+    /// Latency of successful requests at the quantile(0.0 ..= 1.0).
+    pub fn latency_at(&self, q: f64) -> Option<Duration> {
+        Self::at(&self.ok, q)
+    }
+
+    /// Latency of failed requests at the quantile(0.0 ..= 1.0).
+    ///
+    /// Failures may be fast(rejected) or slow(timed out); kept apart from the successes.
+    pub fn err_latency_at(&self, q: f64) -> Option<Duration> {
+        Self::at(&self.err, q)
+    }
+
+    pub fn as_ok_hist(&self) -> &LatencyHist {
+        &self.ok
+    }
+    pub fn as_err_hist(&self) -> &LatencyHist {
+        &self.err
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        writeln!(f, "elapsed:    {:#?}", self.elapsed)?;
+        writeln!(f, "ok:         {}", self.ok_count())?;
+        writeln!(f, "err:        {}", self.err_count())?;
+        writeln!(f, "throughput: {:.1} req/s", self.throughput())?;
+        for (name, q) in [
+            ("min", 0.0),
+            ("p50", 0.5),
+            ("p90", 0.9),
+            ("p99", 0.99),
+            ("max", 1.0),
+        ] {
+            match self.latency_at(q) {
+                None => writeln!(f, "{name}:        -")?,
+                Some(d) => writeln!(f, "{name}:        {d:#?}")?,
+            }
+        }
+        if !self.err.is_empty() {
+            writeln!(f, "err latency: {}", self.err)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use core::str::FromStr;
+use core::time::Duration;
+use std::time::SystemTime;
+
+/// Seeds of ConvertRequest sent by the load generator.
+#[derive(Clone, Default)]
+pub enum Seed {
+    /// current unixtime in microseconds(u64, big endian)
+    #[default]
+    UnixtimeUs,
+
+    /// same bytes for all requests
+    Fixed(Vec<u8>),
+}
+
+impl Seed {
+    pub fn generate(&self) -> Vec<u8> {
+        match self {
+            Self::UnixtimeUs => {
+                let d: Duration = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default();
+                let us: u64 = d.as_micros() as u64;
+                us.to_be_bytes().into()
+            }
+            Self::Fixed(v) => v.clone(),
+        }
+    }
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    /// Parses "unixtime-us" or "fixed:<text>".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unixtime-us" => Ok(Self::UnixtimeUs),
+            _ => s
+                .strip_prefix("fixed:")
+                .map(|t: &str| Self::Fixed(t.as_bytes().into()))
+                .ok_or_else(|| format!("invalid seed mode: {s}")),
+        }
+    }
+}
@@ -1,3 +1,9 @@
+pub mod backoff;
+pub mod budget;
+pub mod deadline;
+pub mod exec;
+pub mod layer;
+
 use core::time::Duration;
 
 use tonic::Status;
@@ -5,25 +11,63 @@ use tonic::Status;
 use crate::rpc::perf::helper;
 use helper::proto::common::v1::Retry as Gretry;
 
+use backoff::{Backoff, Delays};
+
 pub const INTERVAL_DEFAULT: Duration = Duration::from_micros(100);
 pub const INTERVAL_MIN: Duration = Duration::from_nanos(1);
 pub const TIMEOUT_DEFAULT: Duration = Duration::from_millis(100);
 
+#[derive(Clone, Copy, Debug)]
 pub struct Retry {
     retry_max: u64,
     interval: Duration,
-    timeout: Duration,
+    timeout: Option<Duration>,
+    backoff: Backoff,
 }
 
 impl Retry {
+    /// Creates a retry with the fixed backoff.
+    pub fn new(retry_max: u64, interval: Duration, timeout: Duration) -> Self {
+        Self {
+            retry_max,
+            interval,
+            timeout: Some(timeout),
+            backoff: Backoff::Fixed,
+        }
+    }
+
+    /// Sets the backoff which grows the delay(starting from the interval) between retries.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
     pub fn as_retry_max(&self) -> u64 {
         self.retry_max
     }
     pub fn as_interval(&self) -> Duration {
         self.interval.max(INTERVAL_MIN)
     }
+    /// The timeout; [`TIMEOUT_DEFAULT`] if neither the timeout nor the deadline was set.
     pub fn as_timeout(&self) -> Duration {
-        self.timeout
+        self.timeout.unwrap_or(TIMEOUT_DEFAULT)
+    }
+    pub fn as_backoff(&self) -> Backoff {
+        self.backoff
+    }
+
+    /// Shortens the timeout to the request deadline(if any); an unset timeout becomes the deadline.
+    pub fn within(mut self, deadline: Option<Duration>) -> Self {
+        self.timeout = match (self.timeout, deadline) {
+            (Some(t), Some(d)) => Some(t.min(d)),
+            (t, d) => t.or(d),
+        };
+        self
+    }
+
+    /// Creates the delays between retries.
+    pub fn delays(&self) -> Delays {
+        self.backoff.delays(self.as_interval())
     }
 }
 
@@ -31,6 +75,7 @@ pub trait RetryLike {
     fn as_retry_max(&self) -> u64;
     fn as_interval(&self) -> Option<Duration>;
     fn as_timeout(&self) -> Option<Duration>;
+    fn as_backoff(&self) -> Option<Backoff>;
 }
 
 impl RetryLike for Gretry {
@@ -43,6 +88,9 @@ impl RetryLike for Gretry {
     fn as_timeout(&self) -> Option<Duration> {
         self.timeout.clone().and_then(|d| d.try_into().ok())
     }
+    fn as_backoff(&self) -> Option<Backoff> {
+        self.backoff.as_ref().map(|b| b.into())
+    }
 }
 
 impl<R> From<&R> for Retry
@@ -52,11 +100,13 @@ where
     fn from(r: &R) -> Self {
         let retry_max: u64 = r.as_retry_max();
         let interval: Duration = r.as_interval().unwrap_or(INTERVAL_DEFAULT);
-        let timeout: Duration = r.as_timeout().unwrap_or(TIMEOUT_DEFAULT);
+        let timeout: Option<Duration> = r.as_timeout();
+        let backoff: Backoff = r.as_backoff().unwrap_or_default();
         Self {
             retry_max,
             interval,
             timeout,
+            backoff,
         }
     }
 }
@@ -71,3 +121,20 @@ where
         Ok(r.into())
     }
 }
+
+impl From<&Retry> for Gretry {
+    fn from(r: &Retry) -> Self {
+        Self {
+            retry_max: r.retry_max,
+            interval: r.interval.try_into().ok(),
+            timeout: r.timeout.and_then(|d| d.try_into().ok()),
+            backoff: Some((&r.backoff).into()),
+        }
+    }
+}
+
+impl From<Retry> for Gretry {
+    fn from(r: Retry) -> Self {
+        (&r).into()
+    }
+}
@@ -0,0 +1,135 @@
+use core::time::Duration;
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::rpc::perf::helper;
+use helper::proto::common::v1::backoff::{DecorrelatedJitter, Exponential, Fixed, Strategy};
+use helper::proto::common::v1::Backoff as Gbackoff;
+
+pub const MULTIPLIER_DEFAULT: f64 = 2.0;
+pub const CAP_DEFAULT: Duration = Duration::from_secs(1);
+
+/// How the delay between retries grows.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Backoff {
+    /// Waits the interval before each retry.
+    #[default]
+    Fixed,
+
+    /// Multiplies the delay by the multiplier(at least 1) after each retry; up to the cap.
+    Exponential { multiplier: f64, cap: Duration },
+
+    /// Picks a random delay between the interval and 3 times the last delay; up to the cap.
+    DecorrelatedJitter { cap: Duration },
+}
+
+impl Backoff {
+    pub fn exponential(multiplier: f64, cap: Duration) -> Self {
+        let multiplier: f64 = match multiplier.is_finite() {
+            true => multiplier.max(1.0),
+            false => MULTIPLIER_DEFAULT,
+        };
+        Self::Exponential { multiplier, cap }
+    }
+
+    pub fn decorrelated_jitter(cap: Duration) -> Self {
+        Self::DecorrelatedJitter { cap }
+    }
+
+    /// Creates the (endless) delays starting from the interval.
+    pub fn delays(&self, interval: Duration) -> Delays {
+        let seed: u64 = RandomState::new().build_hasher().finish();
+        Delays {
+            backoff: *self,
+            interval,
+            last: None,
+            rng: seed | 1,
+        }
+    }
+}
+
+pub struct Delays {
+    backoff: Backoff,
+    interval: Duration,
+    last: Option<Duration>,
+    rng: u64,
+}
+
+impl Delays {
+    /// Gets the next pseudo random number(xorshift64*).
+    fn random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn between(&mut self, lo: Duration, hi: Duration) -> Duration {
+        let span: u64 = u64::try_from(hi.saturating_sub(lo).as_nanos()).unwrap_or(u64::MAX);
+        let offset: u64 = self.random() % span.saturating_add(1);
+        lo.saturating_add(Duration::from_nanos(offset))
+    }
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+    fn next(&mut self) -> Option<Self::Item> {
+        let base: Duration = self.interval;
+        let delay: Duration = match (self.backoff, self.last) {
+            (Backoff::Fixed, _) => base,
+            (Backoff::Exponential { cap, .. }, None) => base.min(cap.max(base)),
+            (Backoff::Exponential { multiplier, cap }, Some(last)) => {
+                let cap: Duration = cap.max(base);
+                Duration::try_from_secs_f64(last.as_secs_f64() * multiplier)
+                    .unwrap_or(cap)
+                    .min(cap)
+            }
+            (Backoff::DecorrelatedJitter { cap }, last) => {
+                let cap: Duration = cap.max(base);
+                let hi: Duration = last.unwrap_or(base).saturating_mul(3);
+                self.between(base, hi).min(cap)
+            }
+        };
+        self.last = Some(delay);
+        Some(delay)
+    }
+}
+
+fn duration(d: &Option<prost_types::Duration>) -> Option<Duration> {
+    d.clone().and_then(|d| d.try_into().ok())
+}
+
+impl From<&Gbackoff> for Backoff {
+    fn from(g: &Gbackoff) -> Self {
+        match &g.strategy {
+            None | Some(Strategy::Fixed(_)) => Self::Fixed,
+            Some(Strategy::Exponential(e)) => {
+                Self::exponential(e.multiplier, duration(&e.cap).unwrap_or(CAP_DEFAULT))
+            }
+            Some(Strategy::DecorrelatedJitter(j)) => {
+                Self::decorrelated_jitter(duration(&j.cap).unwrap_or(CAP_DEFAULT))
+            }
+        }
+    }
+}
+
+impl From<&Backoff> for Gbackoff {
+    fn from(b: &Backoff) -> Self {
+        let strategy: Strategy = match *b {
+            Backoff::Fixed => Strategy::Fixed(Fixed {}),
+            Backoff::Exponential { multiplier, cap } => Strategy::Exponential(Exponential {
+                multiplier,
+                cap: cap.try_into().ok(),
+            }),
+            Backoff::DecorrelatedJitter { cap } => {
+                Strategy::DecorrelatedJitter(DecorrelatedJitter {
+                    cap: cap.try_into().ok(),
+                })
+            }
+        };
+        Self {
+            strategy: Some(strategy),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use crate::stats::retry::{RetryCounts, RetryStats};
+
+/// Tokens are kept in thousandths to deposit fractions without floats.
+const MILLI: u64 = 1000;
+
+static SHARED: OnceLock<Budget> = OnceLock::new();
+
+/// Gets the budget used by the retry executor unless another one is given.
+///
+/// The budget set by [`install`]; an unlimited one(which only counts the attempts of the
+/// whole process) if nothing was installed before the first use.
+pub fn shared() -> &'static Budget {
+    SHARED.get_or_init(Budget::unlimited)
+}
+
+/// Sets the shared budget; gives it back if the shared budget was already set or used.
+pub fn install(budget: Budget) -> Result<(), Budget> {
+    SHARED.set(budget)
+}
+
+/// A token bucket which caps retries shared by the callers of the retry executor.
+///
+/// The bucket starts full. Every first attempt deposits `ratio` tokens(up to `max_tokens`)
+/// and every retry withdraws a token; a retry is denied if less than a token is left.
+/// e.g, a ratio of 0.1 allows 1 retry per 10 requests once the initial tokens are spent.
+#[derive(Debug)]
+pub struct Budget {
+    max: u64,
+    ratio: u64,
+    tokens: AtomicU64,
+    stats: RetryStats,
+}
+
+impl Budget {
+    pub fn new(max_tokens: u32, ratio: f64) -> Self {
+        let max: u64 = u64::from(max_tokens) * MILLI;
+        let ratio: f64 = match ratio.is_finite() {
+            true => ratio.max(0.0),
+            false => 0.0,
+        };
+        Self {
+            max,
+            ratio: (ratio * MILLI as f64).min(max as f64) as u64,
+            tokens: AtomicU64::new(max),
+            stats: RetryStats::new(),
+        }
+    }
+
+    /// Creates a budget which never denies retries(only counts attempts).
+    pub const fn unlimited() -> Self {
+        Self {
+            max: u64::MAX,
+            ratio: 0,
+            tokens: AtomicU64::new(u64::MAX),
+            stats: RetryStats::new(),
+        }
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.max == u64::MAX
+    }
+
+    /// Records a first attempt.
+    pub fn on_first(&self) {
+        self.stats.record_first();
+        if self.is_unlimited() {
+            return;
+        }
+        let (max, ratio) = (self.max, self.ratio);
+        let _ = self
+            .tokens
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |t| {
+                Some(t.saturating_add(ratio).min(max))
+            });
+    }
+
+    /// Takes a token for a retry; false if the budget ran out.
+    pub fn try_retry(&self) -> bool {
+        let taken: bool = self.is_unlimited()
+            || self
+                .tokens
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |t| {
+                    t.checked_sub(MILLI)
+                })
+                .is_ok();
+        match taken {
+            true => self.stats.record_retry(),
+            false => self.stats.record_denied(),
+        }
+        taken
+    }
+
+    /// Number of tokens left.
+    pub fn as_tokens(&self) -> f64 {
+        self.tokens.load(Ordering::Relaxed) as f64 / MILLI as f64
+    }
+
+    pub fn as_stats(&self) -> &RetryStats {
+        &self.stats
+    }
+
+    pub fn snapshot(&self) -> RetryCounts {
+        self.stats.snapshot()
+    }
+}
@@ -0,0 +1,50 @@
+use core::time::Duration;
+
+use tokio::time::Instant;
+
+use tonic::metadata::MetadataMap;
+
+/// The header of the request deadline(e.g, `100m` for 100 milliseconds).
+pub const GRPC_TIMEOUT: &str = "grpc-timeout";
+
+/// Parses the value of the `grpc-timeout` header: up to 8 digits followed by the unit.
+pub fn parse(v: &str) -> Option<Duration> {
+    let b: &[u8] = v.as_bytes();
+    let (digits, unit) = b.split_at(b.len().checked_sub(1)?);
+    let valid: bool = !digits.is_empty() && digits.len() <= 8;
+    if !valid || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let n: u64 = core::str::from_utf8(digits).ok()?.parse().ok()?;
+    match unit {
+        b"H" => Some(Duration::from_secs(n * 3600)),
+        b"M" => Some(Duration::from_secs(n * 60)),
+        b"S" => Some(Duration::from_secs(n)),
+        b"m" => Some(Duration::from_millis(n)),
+        b"u" => Some(Duration::from_micros(n)),
+        b"n" => Some(Duration::from_nanos(n)),
+        _ => None,
+    }
+}
+
+/// Gets the request deadline(if any); an invalid header is ignored.
+pub fn from_metadata(m: &MetadataMap) -> Option<Duration> {
+    let v: &str = m.get(GRPC_TIMEOUT)?.to_str().ok()?;
+    let parsed: Option<Duration> = parse(v);
+    if parsed.is_none() {
+        log::warn!("invalid {GRPC_TIMEOUT}: {v}");
+    }
+    parsed
+}
+
+/// Gets the deadline of a whole retried operation: the timeout from now(or the request
+/// deadline if earlier).
+pub fn overall(timeout: Duration, until: Option<Instant>) -> Instant {
+    let by_timeout: Instant = Instant::now() + timeout;
+    until.map_or(by_timeout, |u: Instant| u.min(by_timeout))
+}
+
+/// Gets the time left until the deadline(if any).
+pub fn remaining(until: Option<Instant>) -> Option<Duration> {
+    until.map(|u: Instant| u.saturating_duration_since(Instant::now()))
+}
@@ -0,0 +1,125 @@
+use core::future::Future;
+use core::time::Duration;
+
+use tokio::time::Instant;
+
+use tonic::{Code, Status};
+
+use crate::buffer::full::Full;
+use crate::retry::backoff::Delays;
+use crate::retry::budget::{self, Budget};
+use crate::retry::Retry;
+
+/// Checks if the error may go away when retried(e.g, no response yet, a full buffer).
+pub fn is_transient(s: &Status) -> bool {
+    matches!(s.code(), Code::DeadlineExceeded | Code::Unavailable)
+}
+
+/// Runs the operation until it succeeds or fails with an error which is not retryable;
+/// up to `retry_max` retries.
+///
+/// Waits the next delay of the backoff before each retry(or the retry-after hint of a full
+/// buffer if longer). The operation gets the number of the attempt(starting from 0).
+pub async fn run<T, F, Fut, P>(retry: &Retry, retryable: P, op: F) -> Result<T, Status>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+    P: Fn(&Status) -> bool,
+{
+    run_until(retry, None, retryable, op).await
+}
+
+/// Same as [`run`] but gives up(returns the last error) when a retry would start after the
+/// deadline.
+pub async fn run_until<T, F, Fut, P>(
+    retry: &Retry,
+    until: Option<Instant>,
+    retryable: P,
+    op: F,
+) -> Result<T, Status>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+    P: Fn(&Status) -> bool,
+{
+    run_budgeted(retry, until, budget::shared(), retryable, op).await
+}
+
+/// Same as [`run_until`] but each retry takes a token from the budget; gives up(returns the
+/// last error) when the budget ran out.
+pub async fn run_budgeted<T, F, Fut, P>(
+    retry: &Retry,
+    until: Option<Instant>,
+    budget: &Budget,
+    retryable: P,
+    op: F,
+) -> Result<T, Status>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+    P: Fn(&Status) -> bool,
+{
+    Scope::new(budget)
+        .run_until(retry, until, retryable, op)
+        .await
+}
+
+/// Retries the steps of a request(e.g, save then get) on a budget; the request is recorded
+/// as a single first attempt however many steps it has.
+pub struct Scope<'a> {
+    budget: &'a Budget,
+}
+
+impl Scope<'static> {
+    /// Starts a request on the shared budget.
+    pub fn shared() -> Self {
+        Self::new(budget::shared())
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Starts a request; records its first attempt.
+    pub fn new(budget: &'a Budget) -> Self {
+        budget.on_first();
+        Self { budget }
+    }
+
+    /// Runs a step of the request like [`run_until`]; each retry takes a token from the
+    /// budget.
+    pub async fn run_until<T, F, Fut, P>(
+        &self,
+        retry: &Retry,
+        until: Option<Instant>,
+        retryable: P,
+        mut op: F,
+    ) -> Result<T, Status>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+        P: Fn(&Status) -> bool,
+    {
+        let mut delays: Delays = retry.delays();
+        let mut attempt: u64 = 0;
+        loop {
+            let e: Status = match op(attempt).await {
+                Ok(t) => return Ok(t),
+                Err(e) => e,
+            };
+            if retry.as_retry_max() <= attempt || !retryable(&e) {
+                return Err(e);
+            }
+            let hint: Duration = Full::from_status(&e)
+                .map(|f: Full| f.as_retry_after())
+                .unwrap_or_default();
+            let delay: Duration = delays.next().unwrap_or_default().max(hint);
+            let late: bool = until
+                .map(|u: Instant| u <= Instant::now() + delay)
+                .unwrap_or(false);
+            if late || !self.budget.try_retry() {
+                return Err(e);
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Code, Extensions, Request, Response, Status};
+
+use tokio::time::Instant;
+
+use crate::retry::budget::{self, Budget};
+use crate::retry::{deadline, exec, Retry};
+
+use crate::rpc::perf::helper;
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+
+/// The metadata key of the number of attempts(1 if not retried).
+pub const ATTEMPTS: &str = "x-retry-attempts";
+
+pub const CODES_DEFAULT: [Code; 3] = [
+    Code::Unavailable,
+    Code::ResourceExhausted,
+    Code::DeadlineExceeded,
+];
+
+/// A [`ConvertService`] which retries the inner service on the codes using the retry.
+///
+/// The number of attempts is recorded in the metadata([`ATTEMPTS`]) of the response or the
+/// error.
+pub struct Retrying<C> {
+    inner: C,
+    retry: Retry,
+    codes: Vec<Code>,
+    budget: Option<Arc<Budget>>,
+}
+
+impl<C> Retrying<C> {
+    /// Wraps the service; retried on [`CODES_DEFAULT`].
+    pub fn new(inner: C, retry: Retry) -> Self {
+        Self {
+            inner,
+            retry,
+            codes: CODES_DEFAULT.into(),
+            budget: None,
+        }
+    }
+
+    /// Sets the budget(possibly shared with other services) which caps the retries;
+    /// [`budget::shared`] if not set.
+    pub fn budget(mut self, budget: Arc<Budget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Sets the codes to be retried.
+    pub fn codes<I>(mut self, codes: I) -> Self
+    where
+        I: IntoIterator<Item = Code>,
+    {
+        self.codes = codes.into_iter().collect();
+        self
+    }
+
+    pub fn as_inner(&self) -> &C {
+        &self.inner
+    }
+    pub fn as_retry(&self) -> &Retry {
+        &self.retry
+    }
+    pub fn as_codes(&self) -> &[Code] {
+        &self.codes
+    }
+    pub fn as_budget(&self) -> &Budget {
+        self.budget.as_deref().unwrap_or(budget::shared())
+    }
+
+    fn is_retryable(&self, s: &Status) -> bool {
+        self.codes.contains(&s.code())
+    }
+}
+
+/// Gets the number of attempts recorded by [`Retrying`](if any).
+pub fn attempts(m: &MetadataMap) -> Option<u64> {
+    m.get(ATTEMPTS)?.to_str().ok()?.parse().ok()
+}
+
+fn record(m: &mut MetadataMap, attempts: u64) {
+    let v: MetadataValue<_> = attempts.into();
+    m.insert(ATTEMPTS, v);
+}
+
+#[tonic::async_trait]
+impl<C> ConvertService for Retrying<C>
+where
+    C: ConvertService,
+{
+    async fn convert(
+        &self,
+        req: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        let until: Option<Instant> =
+            deadline::from_metadata(req.metadata()).map(|d| Instant::now() + d);
+        let (metadata, _, cr) = req.into_parts();
+        let mut attempts: u64 = 0;
+        let r: Result<Response<ConvertResponse>, Status> = exec::run_budgeted(
+            &self.retry,
+            until,
+            self.as_budget(),
+            |s: &Status| self.is_retryable(s),
+            |_| {
+                attempts += 1;
+                let mut q =
+                    Request::from_parts(metadata.clone(), Extensions::default(), cr.clone());
+                // an attempt gets the time left until the deadline
+                if let Some(left) = deadline::remaining(until) {
+                    q.set_timeout(left);
+                }
+                self.inner.convert(q)
+            },
+        )
+        .await;
+        match r {
+            Ok(mut res) => {
+                record(res.metadata_mut(), attempts);
+                Ok(res)
+            }
+            Err(mut e) => {
+                record(e.metadata_mut(), attempts);
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod hist;
+pub mod pipeline;
+pub mod retry;
@@ -0,0 +1,65 @@
+use core::fmt;
+use core::time::Duration;
+
+use hdrhistogram::Histogram;
+
+use tonic::Status;
+
+/// Quantiles printed by [`LatencyHist`].
+pub const QUANTILES: [(&str, f64); 4] =
+    [("p50", 0.5), ("p90", 0.9), ("p99", 0.99), ("p99.9", 0.999)];
+
+/// Latencies longer than this are recorded as this.
+pub const LATENCY_MAX: Duration = Duration::from_secs(3600);
+
+/// HDR histogram of latencies(unit: us, 3 significant digits).
+pub struct LatencyHist {
+    h: Histogram<u64>,
+}
+
+impl LatencyHist {
+    pub fn new() -> Result<Self, Status> {
+        let high: u64 = LATENCY_MAX.as_micros() as u64;
+        let h: Histogram<u64> = Histogram::new_with_max(high, 3)
+            .map_err(|e| Status::internal(format!("Unable to create a histogram: {e}")))?;
+        Ok(Self { h })
+    }
+
+    pub fn record(&mut self, d: Duration) {
+        let us: u64 = d.as_micros().try_into().unwrap_or(u64::MAX);
+        self.h.saturating_record(us);
+    }
+
+    pub fn merge(&mut self, other: &Self) -> Result<(), Status> {
+        self.h
+            .add(&other.h)
+            .map_err(|e| Status::internal(format!("Unable to merge histograms: {e}")))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.h.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.h.is_empty()
+    }
+
+    /// Latency at the quantile(0.0 ..= 1.0).
+    pub fn quantile(&self, q: f64) -> Duration {
+        Duration::from_micros(self.h.value_at_quantile(q))
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.h.max())
+    }
+}
+
+impl fmt::Display for LatencyHist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "n={}", self.len())?;
+        for (name, q) in QUANTILES {
+            write!(f, " {name}={:#?}", self.quantile(q))?;
+        }
+        write!(f, " max={:#?}", self.max())
+    }
+}
@@ -0,0 +1,172 @@
+use core::fmt;
+use core::time::Duration;
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use prost_types::Timestamp;
+
+use tonic::Status;
+
+use crate::stats::hist::LatencyHist;
+
+use crate::rpc::perf::helper;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+
+/// Stages of the buffered flow.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Stage {
+    /// received -> saved
+    Save,
+
+    /// saved -> loaded
+    QueueWait,
+
+    /// loaded -> converted
+    Convert,
+
+    /// converted -> set
+    Set,
+
+    /// set -> got(by the client)
+    Delivery,
+
+    /// received -> got
+    EndToEnd,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 6] = [
+        Stage::Save,
+        Stage::QueueWait,
+        Stage::Convert,
+        Stage::Set,
+        Stage::Delivery,
+        Stage::EndToEnd,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Save => "save",
+            Self::QueueWait => "queue wait",
+            Self::Convert => "convert",
+            Self::Set => "set",
+            Self::Delivery => "delivery",
+            Self::EndToEnd => "end to end",
+        }
+    }
+}
+
+/// Timestamps of a response.
+#[derive(Default, Clone, Copy)]
+pub struct Timestamps {
+    pub received: Option<SystemTime>,
+    pub saved: Option<SystemTime>,
+    pub loaded: Option<SystemTime>,
+    pub converted: Option<SystemTime>,
+    pub set: Option<SystemTime>,
+    pub got: Option<SystemTime>,
+}
+
+fn ts2time(t: &Option<Timestamp>) -> Option<SystemTime> {
+    t.clone().and_then(|t| SystemTime::try_from(t).ok())
+}
+
+impl Timestamps {
+    /// Gets timestamps from the response got at `got`.
+    pub fn new(gr: &GetResponse, got: SystemTime) -> Self {
+        Self {
+            received: ts2time(&gr.received),
+            saved: ts2time(&gr.saved),
+            loaded: ts2time(&gr.loaded),
+            converted: ts2time(&gr.converted),
+            set: ts2time(&gr.set),
+            got: Some(got),
+        }
+    }
+
+    fn span(&self, stage: Stage) -> (Option<SystemTime>, Option<SystemTime>) {
+        match stage {
+            Stage::Save => (self.received, self.saved),
+            Stage::QueueWait => (self.saved, self.loaded),
+            Stage::Convert => (self.loaded, self.converted),
+            Stage::Set => (self.converted, self.set),
+            Stage::Delivery => (self.set, self.got),
+            Stage::EndToEnd => (self.received, self.got),
+        }
+    }
+
+    /// Duration of the stage; None if a timestamp is missing.
+    /// Err if the stage ended before it started(e.g, clocks of hosts differ).
+    pub fn elapsed(&self, stage: Stage) -> Option<Result<Duration, Duration>> {
+        let (start, end) = self.span(stage);
+        let (start, end) = (start?, end?);
+        Some(end.duration_since(start).map_err(|e| e.duration()))
+    }
+}
+
+/// Histograms of stages.
+pub struct PipelineStats {
+    hists: BTreeMap<Stage, LatencyHist>,
+    skewed: u64,
+}
+
+impl PipelineStats {
+    pub fn new() -> Result<Self, Status> {
+        let mut hists: BTreeMap<Stage, LatencyHist> = BTreeMap::new();
+        for stage in Stage::ALL {
+            hists.insert(stage, LatencyHist::new()?);
+        }
+        Ok(Self { hists, skewed: 0 })
+    }
+
+    pub fn record(&mut self, t: &Timestamps) {
+        for (stage, h) in self.hists.iter_mut() {
+            match t.elapsed(*stage) {
+                None => {}
+                Some(Ok(d)) => h.record(d),
+                Some(Err(_)) => self.skewed += 1,
+            }
+        }
+    }
+
+    /// Records timestamps of the response got at `got`.
+    pub fn ingest(&mut self, gr: &GetResponse, got: SystemTime) {
+        self.record(&Timestamps::new(gr, got))
+    }
+
+    pub fn merge(&mut self, other: &Self) -> Result<(), Status> {
+        for (stage, h) in self.hists.iter_mut() {
+            match other.hists.get(stage) {
+                None => {}
+                Some(o) => h.merge(o)?,
+            }
+        }
+        self.skewed += other.skewed;
+        Ok(())
+    }
+
+    pub fn get(&self, stage: Stage) -> Option<&LatencyHist> {
+        self.hists.get(&stage)
+    }
+
+    /// Number of stages which ended before they started.
+    pub fn as_skewed(&self) -> u64 {
+        self.skewed
+    }
+}
+
+impl fmt::Display for PipelineStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for (stage, h) in self.hists.iter() {
+            match h.is_empty() {
+                true => writeln!(f, "{:<10}: -", stage.as_str())?,
+                false => writeln!(f, "{:<10}: {h}", stage.as_str())?,
+            }
+        }
+        match self.skewed {
+            0 => Ok(()),
+            n => writeln!(f, "skewed:     {n}"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use core::fmt;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::rpc::perf::helper;
+use helper::proto::common::v1::RetryCounts as Gcounts;
+
+/// Counters of attempts shared by the callers of the retry executor.
+#[derive(Default, Debug)]
+pub struct RetryStats {
+    first: AtomicU64,
+    retries: AtomicU64,
+    denied: AtomicU64,
+}
+
+impl RetryStats {
+    pub const fn new() -> Self {
+        Self {
+            first: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            denied: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_first(&self) {
+        self.first.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a retry which was not sent(e.g, the budget ran out).
+    pub fn record_denied(&self) {
+        self.denied.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> RetryCounts {
+        RetryCounts {
+            first: self.first.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            denied: self.denied.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Numbers of attempts at a point in time.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryCounts {
+    first: u64,
+    retries: u64,
+    denied: u64,
+}
+
+impl RetryCounts {
+    pub fn as_first(&self) -> u64 {
+        self.first
+    }
+    pub fn as_retries(&self) -> u64 {
+        self.retries
+    }
+    pub fn as_denied(&self) -> u64 {
+        self.denied
+    }
+
+    /// Attempts per first attempt(1.0 if nothing was retried).
+    pub fn amplification(&self) -> f64 {
+        match self.first {
+            0 => 1.0,
+            n => (n + self.retries) as f64 / n as f64,
+        }
+    }
+}
+
+impl From<RetryCounts> for Gcounts {
+    fn from(c: RetryCounts) -> Self {
+        Self {
+            first: c.first,
+            retries: c.retries,
+            denied: c.denied,
+        }
+    }
+}
+
+impl From<&Gcounts> for RetryCounts {
+    fn from(g: &Gcounts) -> Self {
+        Self {
+            first: g.first,
+            retries: g.retries,
+            denied: g.denied,
+        }
+    }
+}
+
+impl fmt::Display for RetryCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "first: {}, retries: {}, denied: {}, amplification: {:.3}",
+            self.first,
+            self.retries,
+            self.denied,
+            self.amplification(),
+        )
+    }
+}
@@ -1,11 +1,23 @@
 use core::fmt;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(not(any(feature = "uv4", feature = "uv7")))]
+use std::collections::hash_map::RandomState;
+#[cfg(not(any(feature = "uv4", feature = "uv7")))]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(not(any(feature = "uv4", feature = "uv7")))]
+use std::sync::atomic::AtomicU64;
+#[cfg(not(any(feature = "uv4", feature = "uv7")))]
+use std::sync::OnceLock;
+
 use tonic::Status;
 
 use crate::rpc::perf::helper;
 use helper::proto::common::v1::Uuid as Cuid;
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Clone, Copy)]
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Clone, Copy, Debug)]
 pub struct Uuid {
     raw: u128,
 }
@@ -17,6 +29,62 @@ impl Uuid {
         let raw: u128 = u.as_u128();
         Self { raw }
     }
+}
+
+#[cfg(feature = "uv7")]
+impl Uuid {
+    /// Creates a time ordered id; ids created later in the process are greater.
+    pub fn now_v7() -> Self {
+        let u: uuid::Uuid = uuid::Uuid::now_v7();
+        let raw: u128 = u.as_u128();
+        Self { raw }
+    }
+}
+
+impl Uuid {
+    pub const fn from_u128(raw: u128) -> Self {
+        Self { raw }
+    }
+
+    pub const fn as_u128(&self) -> u128 {
+        self.raw
+    }
+
+    /// Creates a new id.
+    ///
+    /// Uses the seeded generator if [`seed`] is set; otherwise a time ordered id(v7) with the
+    /// `uv7` feature, a random id(v4) with the `uv4` feature or an id of a generator seeded
+    /// randomly at the start of the process.
+    pub fn generate() -> Self {
+        match SEEDED_ON.load(Ordering::Relaxed) {
+            true => Self::generate_seeded().unwrap_or_else(Self::generate_unseeded),
+            false => Self::generate_unseeded(),
+        }
+    }
+
+    fn generate_seeded() -> Option<Self> {
+        let mut g = SEEDED.lock().unwrap_or_else(PoisonError::into_inner);
+        g.as_mut().and_then(|s: &mut Seeded| s.next())
+    }
+
+    #[cfg(feature = "uv7")]
+    fn generate_unseeded() -> Self {
+        Self::now_v7()
+    }
+
+    #[cfg(all(feature = "uv4", not(feature = "uv7")))]
+    fn generate_unseeded() -> Self {
+        Self::new_v4()
+    }
+
+    #[cfg(not(any(feature = "uv4", feature = "uv7")))]
+    fn generate_unseeded() -> Self {
+        static SEED: OnceLock<u64> = OnceLock::new();
+        static COUNT: AtomicU64 = AtomicU64::new(0);
+        let seed: u64 = *SEED.get_or_init(|| RandomState::new().build_hasher().finish());
+        let count: u64 = COUNT.fetch_add(2, Ordering::Relaxed);
+        v4_layout(splitmix64(seed ^ count), splitmix64(seed ^ (count + 1)))
+    }
 
     fn new(hi: u64, lo: u64) -> Self {
         let h: u128 = hi.into();
@@ -32,6 +100,57 @@ impl Uuid {
     }
 }
 
+static SEEDED_ON: AtomicBool = AtomicBool::new(false);
+static SEEDED: Mutex<Option<Seeded>> = Mutex::new(None);
+
+/// Makes [`Uuid::generate`] deterministic(e.g, for reproducible test runs); None restores
+/// the default generator.
+pub fn seed(seed: Option<u64>) {
+    let mut g = SEEDED.lock().unwrap_or_else(PoisonError::into_inner);
+    *g = seed.map(Seeded::new);
+    SEEDED_ON.store(seed.is_some(), Ordering::Relaxed);
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z: u64 = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Sets the version(4) and the variant bits.
+fn v4_layout(hi: u64, lo: u64) -> Uuid {
+    let hi: u64 = (hi & !0xf000) | 0x4000;
+    let lo: u64 = (lo & !(0b11 << 62)) | (0b10 << 62);
+    Uuid::new(hi, lo)
+}
+
+/// Deterministic ids(laid out as v4) generated from the seed.
+#[derive(Clone, Debug)]
+pub struct Seeded {
+    state: u64,
+}
+
+impl Seeded {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(1);
+        splitmix64(self.state)
+    }
+}
+
+impl Iterator for Seeded {
+    type Item = Uuid;
+    fn next(&mut self) -> Option<Self::Item> {
+        let hi: u64 = self.next_u64();
+        let lo: u64 = self.next_u64();
+        Some(v4_layout(hi, lo))
+    }
+}
+
 impl fmt::Display for Uuid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "{:032x}", self.raw)
@@ -0,0 +1,90 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::actor::{ActorConfig, FullPolicy};
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_with_config;
+use rs_perf_test_helper::buffer::res::sharded::svc::sharded_res_buffer_service_with_config;
+use rs_perf_test_helper::buffer::vecdeque::svc::request_buffer_service_with_config;
+
+use helper::proto::buffer::v1::req_buf::LoadResponse;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buf::LenRequest;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{load, reply_id, save_req, set_req};
+
+// a buffer with max size 1 keeps one entry
+const MAX_SIZE: usize = 1;
+
+const LOAD_TIMEOUT: Duration = Duration::from_millis(10);
+
+#[tokio::test]
+async fn blocked_saves_wait_for_room() {
+    let cfg = ActorConfig::new(4, FullPolicy::Block(Duration::from_secs(10)));
+    let buf = request_buffer_service_with_config(&cfg, MAX_SIZE).await;
+    let (first, second) = (Uuid::generate(), Uuid::generate());
+    buf.save(Request::new(save_req(first))).await.unwrap();
+
+    let (saved, loaded) = tokio::join!(buf.save(Request::new(save_req(second))), async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        load(&buf, LOAD_TIMEOUT).await
+    });
+    saved.unwrap();
+    assert_eq!(reply_id(&loaded.unwrap()), first);
+    let lr: LoadResponse = load(&buf, LOAD_TIMEOUT).await.unwrap();
+    assert_eq!(reply_id(&lr), second);
+}
+
+#[tokio::test]
+async fn oldest_requests_are_dropped() {
+    let cfg = ActorConfig::new(1, FullPolicy::DropOldest);
+    let buf = request_buffer_service_with_config(&cfg, MAX_SIZE).await;
+    let (first, second) = (Uuid::generate(), Uuid::generate());
+    buf.save(Request::new(save_req(first))).await.unwrap();
+    buf.save(Request::new(save_req(second))).await.unwrap();
+    let lr: LoadResponse = load(&buf, LOAD_TIMEOUT).await.unwrap();
+    assert_eq!(reply_id(&lr), second);
+}
+
+#[tokio::test]
+async fn blocked_sets_are_rejected_after_the_wait() {
+    let wait = Duration::from_millis(20);
+    let cfg = ActorConfig::new(1, FullPolicy::Block(wait));
+    let buf = res_buffer_service_with_config(&cfg, MAX_SIZE).await;
+    buf.set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap();
+
+    let started: Instant = Instant::now();
+    let full: Status = buf
+        .set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    assert_eq!(full.code(), Code::Unavailable);
+    assert!(wait <= started.elapsed());
+}
+
+#[tokio::test]
+async fn shards_use_the_config() {
+    let cfg = ActorConfig::new(1, FullPolicy::DropOldest);
+    let buf = sharded_res_buffer_service_with_config(&cfg, 1, MAX_SIZE)
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        buf.set(Request::new(set_req(Uuid::generate())))
+            .await
+            .unwrap();
+    }
+    let req = LenRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let len = buf.len(Request::new(req)).await.unwrap().into_inner();
+    assert_eq!(len.length, MAX_SIZE as u64);
+}
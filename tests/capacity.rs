@@ -0,0 +1,97 @@
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::buffer::full::Full;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::vecdeque::svc::request_buffer_service_new;
+
+use helper::proto::buffer::v1::req_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{save_req, set_req};
+
+const MAX_SIZE: usize = 3;
+
+async fn stats<B>(b: &B) -> StatsResponse
+where
+    B: ReqBufferService,
+{
+    let req = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    b.stats(Request::new(req)).await.unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn request_buffer_keeps_exactly_max_size() {
+    let buf = request_buffer_service_new(MAX_SIZE).await;
+    for _ in 0..MAX_SIZE {
+        buf.save(Request::new(save_req(Uuid::generate())))
+            .await
+            .unwrap();
+    }
+    let full: Status = buf
+        .save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    assert_eq!(full.code(), Code::Unavailable);
+
+    let f: Full = Full::from_status(&full).unwrap();
+    assert_eq!(f.as_size(), MAX_SIZE as u64);
+    assert_eq!(f.as_limit(), MAX_SIZE as u64);
+}
+
+#[tokio::test]
+async fn res_buffer_keeps_exactly_max_size() {
+    let buf = res_buffer_service_new(MAX_SIZE).await;
+    for _ in 0..MAX_SIZE {
+        buf.set(Request::new(set_req(Uuid::generate())))
+            .await
+            .unwrap();
+    }
+    let full: Status = buf
+        .set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    let f: Full = Full::from_status(&full).unwrap();
+    assert_eq!(f.as_size(), MAX_SIZE as u64);
+    assert_eq!(f.as_limit(), MAX_SIZE as u64);
+}
+
+#[tokio::test]
+async fn zero_max_size_keeps_nothing() {
+    let buf = request_buffer_service_new(0).await;
+    let full: Status = buf
+        .save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    assert_eq!(Full::from_status(&full).unwrap().as_size(), 0);
+}
+
+#[tokio::test]
+async fn stats_reports_capacity_and_pending() {
+    let buf = request_buffer_service_new(MAX_SIZE).await;
+    let empty: StatsResponse = stats(&buf).await;
+    assert_eq!(empty.capacity, MAX_SIZE as u64);
+    assert_eq!(empty.pending, 0);
+    assert_eq!(empty.oldest_age, None);
+
+    buf.save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap();
+    let one: StatsResponse = stats(&buf).await;
+    assert_eq!(one.pending, 1);
+    assert_eq!(one.in_flight, 0);
+    assert!(one.oldest_age.is_some());
+}
+
+#[test]
+fn other_errors_have_no_details() {
+    assert_eq!(Full::from_status(&Status::unavailable("down")), None);
+    assert_eq!(Full::from_status(&Status::internal("oops")), None);
+}
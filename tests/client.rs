@@ -0,0 +1,87 @@
+#![cfg(feature = "client")]
+
+use core::time::Duration;
+use std::time::SystemTime;
+
+use rs_perf_test_helper::tonic;
+use tonic::transport::Server;
+use tonic::Code;
+
+use rs_perf_test_helper::retry::Retry;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::vecdeque::svc::acked_request_buffer_service_new;
+use rs_perf_test_helper::client::buffer::req::ReqBufferClient;
+use rs_perf_test_helper::client::buffer::res::ResBufferClient;
+use rs_perf_test_helper::client::convert::ConvertClient;
+use rs_perf_test_helper::convert::buffer::svc::buffered_convert_service_new;
+
+use helper::proto::buffer::v1::req_buf::LoadResponse;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferServiceServer;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferServiceServer;
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use helper::proto::direct::v1::convert_service_server::ConvertServiceServer;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{echo, serve};
+
+fn retry() -> Retry {
+    Retry::new(1, Duration::from_millis(1), Duration::from_millis(100))
+}
+
+#[tokio::test]
+async fn buffer_clients_round_trip() {
+    let req_svc = acked_request_buffer_service_new(4, Duration::from_secs(60)).await;
+    let res_svc = res_buffer_service_new(4).await;
+    let url: String = serve(
+        Server::builder()
+            .add_service(ReqBufferServiceServer::new(req_svc))
+            .add_service(ResBufferServiceServer::new(res_svc)),
+    )
+    .await;
+    let mut reqc: ReqBufferClient = ReqBufferClient::connect(url.clone()).await.unwrap();
+    let mut resc: ResBufferClient = ResBufferClient::connect(url).await.unwrap();
+
+    let reply_id: Uuid = Uuid::generate();
+    let received: SystemTime = SystemTime::now();
+    reqc.save(reply_id, ConvertRequest::default(), received)
+        .await
+        .unwrap();
+    let mut ls = reqc.load(&retry()).await.unwrap();
+    let lr: LoadResponse = ls.message().await.unwrap().unwrap();
+    assert_eq!(lr.reply_id.clone().map(Uuid::from), Some(reply_id));
+    reqc.ack(lr.delivery_id.clone().unwrap().into())
+        .await
+        .unwrap();
+    assert_eq!(reqc.stats().await.unwrap().in_flight, 0);
+
+    resc.set(
+        reply_id,
+        ConvertResponse::default(),
+        lr.received.unwrap(),
+        lr.saved.unwrap(),
+        lr.loaded,
+    )
+    .await
+    .unwrap();
+    assert_eq!(resc.len().await.unwrap(), 1);
+    let got: GetResponse = resc.get(reply_id, &retry()).await.unwrap();
+    assert_eq!(got.received, Some(received.into()));
+    assert!(resc.is_empty().await.unwrap());
+
+    let missing = resc.del(reply_id).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn convert_client_round_trip() {
+    let svc = buffered_convert_service_new(4, 4, retry()).await;
+    tokio::spawn(echo(svc.as_req_svc().clone(), svc.as_res_svc().clone()));
+    let url: String = serve(Server::builder().add_service(ConvertServiceServer::new(svc))).await;
+    let mut client: ConvertClient = ConvertClient::connect(url).await.unwrap();
+    let res: ConvertResponse = client.convert(b"seed".to_vec()).await.unwrap();
+    assert_eq!(res.generated, b"seed");
+}
@@ -0,0 +1,138 @@
+//! Requests and calls shared by the integration tests.
+
+// each test uses a part of the fixtures
+#![allow(dead_code)]
+
+use core::time::Duration;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use futures::StreamExt;
+
+use tokio::net::{TcpListener, TcpStream};
+
+use rs_perf_test_helper::log;
+use rs_perf_test_helper::tonic;
+use tonic::transport::server::Router;
+use tonic::{Request, Status};
+
+use rs_perf_test_helper::retry::Retry;
+use rs_perf_test_helper::uuid::Uuid;
+
+use helper::proto::buffer::v1::req_buf::{LoadRequest, LoadResponse, SaveRequest};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buf::{GetRequest, GetResponse, LenRequest, SetRequest};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use rs_perf_test_helper::rpc::perf::helper;
+
+pub fn save_req(reply_id: Uuid) -> SaveRequest {
+    SaveRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(reply_id.into()),
+        req: Some(ConvertRequest::default()),
+        received: Some(SystemTime::now().into()),
+    }
+}
+
+pub fn set_req(reply_id: Uuid) -> SetRequest {
+    let now = SystemTime::now();
+    SetRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(reply_id.into()),
+        res: Some(ConvertResponse::default()),
+        received: Some(now.into()),
+        saved: Some(now.into()),
+        converted: Some(now.into()),
+        loaded: None,
+    }
+}
+
+/// Loads up to `max_count` requests; each waits up to the timeout.
+pub async fn load_many<B>(b: &B, timeout: Duration, max_count: u64) -> Result<B::LoadStream, Status>
+where
+    B: ReqBufferService,
+{
+    let retry = Retry::new(1, Duration::from_secs(1), timeout);
+    let req = LoadRequest {
+        request_id: Some(Uuid::generate().into()),
+        retry: Some((&retry).into()),
+        max_count,
+        window: 2,
+    };
+    Ok(b.load(Request::new(req)).await?.into_inner())
+}
+
+pub async fn load<B>(b: &B, timeout: Duration) -> Result<LoadResponse, Status>
+where
+    B: ReqBufferService,
+{
+    let ls: B::LoadStream = load_many(b, timeout, 1).await?;
+    Box::pin(ls).next().await.unwrap()
+}
+
+pub fn reply_id(res: &LoadResponse) -> Uuid {
+    res.reply_id.clone().unwrap().into()
+}
+
+pub fn delivery_id(res: &LoadResponse) -> Uuid {
+    res.delivery_id.clone().unwrap().into()
+}
+
+pub async fn get<B>(b: &B, reply_id: Uuid, timeout: Duration) -> Result<GetResponse, Status>
+where
+    B: ResBufferService,
+{
+    let retry = Retry::new(1, Duration::from_secs(1), timeout);
+    let req = GetRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(reply_id.into()),
+        retry: Some((&retry).into()),
+    };
+    let gs: B::GetStream = b.get(Request::new(req)).await?.into_inner();
+    Box::pin(gs).next().await.unwrap()
+}
+
+pub async fn len<B>(b: &B) -> u64
+where
+    B: ResBufferService,
+{
+    let req = LenRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    b.len(Request::new(req)).await.unwrap().into_inner().length
+}
+
+/// Serves the services on a local port; returns the url to connect to.
+pub async fn serve(router: Router) -> String {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+    let incoming = futures::stream::unfold(listener, |l: TcpListener| async move {
+        let r: Result<TcpStream, _> = l.accept().await.map(|(s, _)| s);
+        Some((r, l))
+    });
+    tokio::spawn(router.serve_with_incoming(incoming));
+    format!("http://{addr}")
+}
+
+/// Converts the requests saved to the request buffer(generates the seeds as they are).
+pub async fn echo<Q, S>(req_svc: Arc<Q>, res_svc: Arc<S>)
+where
+    Q: ReqBufferService,
+    S: ResBufferService,
+{
+    loop {
+        let Ok(lr) = load(req_svc.as_ref(), Duration::from_secs(1)).await else {
+            continue;
+        };
+        let mut sr: SetRequest = set_req(reply_id(&lr));
+        sr.res = Some(ConvertResponse {
+            converted: Some(SystemTime::now().into()),
+            generated: lr.req.map(|r| r.seed).unwrap_or_default(),
+        });
+        if let Err(e) = res_svc.set(Request::new(sr)).await {
+            log::warn!("Unable to set a response: {e}");
+        }
+    }
+}
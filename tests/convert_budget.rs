@@ -0,0 +1,28 @@
+//! Uses the shared retry budget; kept in its own binary so that no other test records
+//! attempts at the same time.
+
+use core::time::Duration;
+
+use rs_perf_test_helper::tonic;
+use tonic::Request;
+
+use rs_perf_test_helper::convert::buffer::svc::buffered_convert_service_new;
+use rs_perf_test_helper::retry::{budget, Retry};
+use rs_perf_test_helper::stats::retry::RetryCounts;
+
+use helper::proto::direct::v1::conv_svc::ConvertRequest;
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+#[tokio::test]
+async fn a_convert_is_a_single_first_attempt() {
+    // saved but never converted
+    let retry = Retry::new(1, Duration::from_millis(1), Duration::from_millis(20));
+    let svc = buffered_convert_service_new(4, 4, retry).await;
+    let before: RetryCounts = budget::shared().snapshot();
+    svc.convert(Request::new(ConvertRequest::default()))
+        .await
+        .unwrap_err();
+    let after: RetryCounts = budget::shared().snapshot();
+    assert_eq!(after.as_first() - before.as_first(), 1);
+}
@@ -0,0 +1,53 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Status};
+
+use rs_perf_test_helper::retry::Retry;
+
+use rs_perf_test_helper::buffer::res::btree::svc::BufSvcSt as ResBufSvc;
+use rs_perf_test_helper::buffer::vecdeque::svc::BufSvcSt as ReqBufSvc;
+use rs_perf_test_helper::convert::buffer::svc::{buffered_convert_service_new, Buffered};
+
+fn retry() -> Retry {
+    Retry::new(1, Duration::from_millis(1), Duration::from_millis(100))
+}
+
+fn missing(r: Result<Buffered<ReqBufSvc, ResBufSvc>, Status>, field: &str) {
+    let e: Status = r.err().unwrap();
+    assert_eq!(e.code(), Code::InvalidArgument);
+    assert_eq!(e.message(), format!("{field} missing"));
+}
+
+#[tokio::test]
+async fn builders_require_all_the_fields() {
+    let svc = buffered_convert_service_new(4, 4, retry()).await;
+    let (req_svc, res_svc): (Arc<ReqBufSvc>, Arc<ResBufSvc>) =
+        (svc.as_req_svc().clone(), svc.as_res_svc().clone());
+
+    let built = Buffered::builder()
+        .req_svc(req_svc.clone())
+        .res_svc(res_svc.clone())
+        .retry(retry())
+        .build();
+    assert!(built.is_ok());
+
+    let no_req = Buffered::builder()
+        .res_svc(res_svc.clone())
+        .retry(retry())
+        .build();
+    missing(no_req, "request buffer");
+
+    let no_res = Buffered::builder()
+        .req_svc(req_svc.clone())
+        .retry(retry())
+        .build();
+    missing(no_res, "response buffer");
+
+    let no_retry = Buffered::builder()
+        .req_svc(req_svc)
+        .res_svc(res_svc)
+        .build();
+    missing(no_retry, "retry");
+}
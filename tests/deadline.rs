@@ -0,0 +1,163 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use futures::StreamExt;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::retry::{deadline, Retry, TIMEOUT_DEFAULT};
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::vecdeque::svc::request_buffer_service_new;
+use rs_perf_test_helper::convert::buffer::svc::buffered_convert_service_new;
+use rs_perf_test_helper::indirect::req::get::svc::Buffered;
+
+use helper::proto::buffer::v1::req_buf::LoadRequest;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buf::GetRequest;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use helper::proto::common::v1::Retry as Gretry;
+use helper::proto::direct::v1::conv_svc::ConvertRequest;
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::set_req;
+
+const DEADLINE: Duration = Duration::from_millis(20);
+
+// far longer than the deadline
+fn retry() -> Retry {
+    Retry::new(0, Duration::from_millis(1), Duration::from_secs(10))
+}
+
+fn with_deadline<T>(msg: T) -> Request<T> {
+    let mut req: Request<T> = Request::new(msg);
+    req.set_timeout(DEADLINE);
+    req
+}
+
+#[test]
+fn timeouts_are_parsed() {
+    assert_eq!(deadline::parse("100m"), Some(Duration::from_millis(100)));
+    assert_eq!(deadline::parse("2H"), Some(Duration::from_secs(7200)));
+    assert_eq!(deadline::parse("3M"), Some(Duration::from_secs(180)));
+    assert_eq!(deadline::parse("4S"), Some(Duration::from_secs(4)));
+    assert_eq!(deadline::parse("5u"), Some(Duration::from_micros(5)));
+    assert_eq!(
+        deadline::parse("99999999n"),
+        Some(Duration::from_nanos(99999999))
+    );
+}
+
+#[test]
+fn invalid_timeouts_are_rejected() {
+    for v in ["", "m", "100", "100x", "123456789m", "-1m", "1.5S", "1é"] {
+        assert_eq!(deadline::parse(v), None, "{v}");
+    }
+}
+
+#[tokio::test]
+async fn load_honors_the_request_deadline() {
+    let buf = request_buffer_service_new(4).await;
+    let req = LoadRequest {
+        request_id: Some(Uuid::generate().into()),
+        retry: Some((&retry()).into()),
+        max_count: 1,
+        window: 1,
+    };
+    let started: Instant = Instant::now();
+    let ls = buf.load(with_deadline(req)).await.unwrap().into_inner();
+    let e: Status = Box::pin(ls).next().await.unwrap().unwrap_err();
+    assert_eq!(e.code(), Code::DeadlineExceeded);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn get_honors_the_request_deadline() {
+    let buf = res_buffer_service_new(4).await;
+    let req = GetRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(Uuid::generate().into()),
+        retry: Some((&retry()).into()),
+    };
+    let started: Instant = Instant::now();
+    let gs = buf.get(with_deadline(req)).await.unwrap().into_inner();
+    let e: Status = Box::pin(gs).next().await.unwrap().unwrap_err();
+    assert_eq!(e.code(), Code::DeadlineExceeded);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn convert_passes_the_deadline_downstream() {
+    let svc = buffered_convert_service_new(4, 4, retry()).await;
+    let started: Instant = Instant::now();
+    let e: Status = svc
+        .convert(with_deadline(ConvertRequest::default()))
+        .await
+        .unwrap_err();
+    assert_eq!(e.code(), Code::DeadlineExceeded);
+    assert!(DEADLINE <= started.elapsed());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn an_unset_timeout_waits_until_the_deadline() {
+    let buf = res_buffer_service_new(4).await;
+    let reply: Uuid = Uuid::generate();
+    let mut retry: Gretry = (&retry()).into();
+    retry.timeout = None;
+    let req = GetRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(reply.into()),
+        retry: Some(retry),
+    };
+    let mut get: Request<GetRequest> = Request::new(req);
+    get.set_timeout(Duration::from_secs(5));
+    let gs = buf.get(get).await.unwrap().into_inner();
+
+    // later than the default timeout
+    tokio::time::sleep(TIMEOUT_DEFAULT * 2).await;
+    buf.set(Request::new(set_req(reply))).await.unwrap();
+    let got = Box::pin(gs).next().await.unwrap().unwrap();
+    assert!(got.res.is_some());
+}
+
+#[test]
+fn the_default_timeout_is_used_without_a_deadline() {
+    let retry: Retry = (&Gretry::default()).into();
+    assert_eq!(retry.within(None).as_timeout(), TIMEOUT_DEFAULT);
+    let d: Duration = Duration::from_secs(3);
+    assert_eq!(retry.within(Some(d)).as_timeout(), d);
+}
+
+#[tokio::test]
+async fn retries_stay_within_the_timeout() {
+    // no consumer sets the response
+    let timeout: Duration = Duration::from_millis(20);
+    let retry = Retry::new(50, Duration::from_millis(1), timeout);
+    let svc = buffered_convert_service_new(4, 4, retry).await;
+    let started: Instant = Instant::now();
+    let e: Status = svc
+        .convert(Request::new(ConvertRequest::default()))
+        .await
+        .unwrap_err();
+    assert_eq!(e.code(), Code::DeadlineExceeded);
+    assert!(started.elapsed() < timeout * 5, "{:?}", started.elapsed());
+}
+
+#[tokio::test]
+async fn indirect_loads_stay_within_the_timeout() {
+    // no request is saved
+    let timeout: Duration = Duration::from_millis(20);
+    let retry = Retry::new(50, Duration::from_millis(1), timeout);
+    let buf = request_buffer_service_new(4).await;
+    let started: Instant = Instant::now();
+    let e: Status = Buffered::load_retry(&buf, Uuid::generate(), (&retry).into())
+        .await
+        .unwrap_err();
+    assert_eq!(e.code(), Code::DeadlineExceeded);
+    assert!(started.elapsed() < timeout * 5, "{:?}", started.elapsed());
+}
@@ -0,0 +1,138 @@
+use core::time::Duration;
+
+use futures::StreamExt;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::res::expire::auto::svc::{auto_expire_svc_new, AutoExpireSvc};
+use rs_perf_test_helper::buffer::res::expire::count::svc::expire_service_new;
+use rs_perf_test_helper::buffer::res::expire::svc::ExpireService;
+
+use helper::proto::buffer::v1::res_buf::DelRequest;
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{len, set_req};
+
+async fn expired<E>(e: &E) -> Vec<Uuid>
+where
+    E: ExpireService,
+{
+    let keys: E::ExpiredKeysStream = e.expired_keys().await.unwrap();
+    let keys: Vec<Result<Uuid, Status>> = keys.collect().await;
+    keys.into_iter().map(|r| r.unwrap()).collect()
+}
+
+#[tokio::test]
+async fn keys_expire_after_max_cnt_ticks() {
+    let e = expire_service_new(3).await;
+    let key: Uuid = Uuid::generate();
+    e.register_key(key).await.unwrap();
+
+    assert!(expired(&e).await.is_empty());
+    e.tick().await.unwrap();
+    e.tick().await.unwrap();
+    assert!(expired(&e).await.is_empty());
+    e.tick().await.unwrap();
+    assert_eq!(expired(&e).await, vec![key]);
+}
+
+#[tokio::test]
+async fn touch_bumps_a_single_key() {
+    let e = expire_service_new(2).await;
+    let touched: Uuid = Uuid::generate();
+    let untouched: Uuid = Uuid::generate();
+    e.register_key(touched).await.unwrap();
+    e.register_key(untouched).await.unwrap();
+
+    e.touch_key(touched).await.unwrap();
+    e.tick().await.unwrap();
+    assert_eq!(expired(&e).await, vec![touched]);
+
+    let missing: Status = e.touch_key(Uuid::generate()).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn forgotten_keys_never_expire() {
+    let e = expire_service_new(1).await;
+    let key: Uuid = Uuid::generate();
+    e.register_key(key).await.unwrap();
+    e.forget_key(key).await.unwrap();
+    e.tick().await.unwrap();
+    assert!(expired(&e).await.is_empty());
+
+    let missing: Status = e.forget_key(key).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn sweeps_remove_abandoned_responses() {
+    let buf = res_buffer_service_new(16).await;
+    let e = expire_service_new(2).await;
+    let key: Uuid = Uuid::generate();
+    buf.set(Request::new(set_req(key))).await.unwrap();
+    e.register_key(key).await.unwrap();
+
+    assert_eq!(AutoExpireSvc::sweep(&buf, &e).await.unwrap(), 0);
+    assert_eq!(len(&buf).await, 1);
+    assert_eq!(AutoExpireSvc::sweep(&buf, &e).await.unwrap(), 1);
+    assert_eq!(len(&buf).await, 0);
+    assert_eq!(AutoExpireSvc::sweep(&buf, &e).await.unwrap(), 0);
+}
+
+const SWEEP_INTERVAL: Duration = Duration::from_millis(10);
+
+#[tokio::test(start_paused = true)]
+async fn the_sweeper_removes_expired_responses() {
+    let buf = res_buffer_service_new(16).await;
+    let e = expire_service_new(2).await;
+    let (svc, sweeper) = auto_expire_svc_new(buf, e, SWEEP_INTERVAL).await;
+    svc.set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap();
+    assert_eq!(len(&svc).await, 1);
+
+    tokio::time::sleep(SWEEP_INTERVAL * 5).await;
+    assert_eq!(len(&svc).await, 0);
+    sweeper.shutdown().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_stopped_sweeper_removes_nothing() {
+    let buf = res_buffer_service_new(16).await;
+    let e = expire_service_new(2).await;
+    let (svc, sweeper) = auto_expire_svc_new(buf, e, SWEEP_INTERVAL).await;
+    sweeper.shutdown().await.unwrap();
+    svc.set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(SWEEP_INTERVAL * 5).await;
+    assert_eq!(len(&svc).await, 1);
+}
+
+#[tokio::test]
+async fn keys_of_rejected_responses_are_forgotten() {
+    let buf = res_buffer_service_new(1).await;
+    let e = expire_service_new(2).await;
+    let (svc, sweeper) = auto_expire_svc_new(buf, e, Duration::from_secs(60)).await;
+    let (kept, rejected) = (Uuid::generate(), Uuid::generate());
+    svc.set(Request::new(set_req(kept))).await.unwrap();
+    let full: Status = svc.set(Request::new(set_req(rejected))).await.unwrap_err();
+    assert_eq!(full.code(), Code::Unavailable);
+
+    // the rejected response can be set again once the buffer has room
+    let req = DelRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(kept.into()),
+    };
+    svc.del(Request::new(req)).await.unwrap();
+    svc.set(Request::new(set_req(rejected))).await.unwrap();
+    sweeper.shutdown().await.unwrap();
+}
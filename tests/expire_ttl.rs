@@ -0,0 +1,46 @@
+use core::time::Duration;
+
+use futures::StreamExt;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::expire::svc::ExpireService;
+use rs_perf_test_helper::buffer::res::expire::ttl::svc::expire_service_new;
+
+async fn expired<E>(e: &E) -> Vec<Uuid>
+where
+    E: ExpireService,
+{
+    let keys: E::ExpiredKeysStream = e.expired_keys().await.unwrap();
+    let keys: Vec<Result<Uuid, Status>> = keys.collect().await;
+    keys.into_iter().map(|r| r.unwrap()).collect()
+}
+
+#[tokio::test]
+async fn keys_expire_in_deadline_order() {
+    let e = expire_service_new(Duration::from_millis(50)).await;
+    let first: Uuid = Uuid::generate();
+    let second: Uuid = Uuid::generate();
+    e.register_key(first).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    e.register_key(second).await.unwrap();
+
+    assert!(expired(&e).await.is_empty());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(expired(&e).await, vec![first, second]);
+}
+
+#[tokio::test]
+async fn forgotten_keys_never_expire() {
+    let e = expire_service_new(Duration::ZERO).await;
+    let key: Uuid = Uuid::generate();
+    e.register_key(key).await.unwrap();
+    e.forget_key(key).await.unwrap();
+    assert!(expired(&e).await.is_empty());
+
+    let missing: Status = e.forget_key(key).await.unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
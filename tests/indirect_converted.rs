@@ -0,0 +1,115 @@
+use core::time::Duration;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::vecdeque::svc::acked_request_buffer_service_new;
+use rs_perf_test_helper::indirect::evt::converted::svc::acked_indirect_service_new;
+use rs_perf_test_helper::indirect::evt::converted::svc::indirect_service_new;
+
+use helper::proto::buffer::v1::req_buf::{LoadResponse, StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::buffer::v1::res_buf::GetResponse;
+use helper::proto::direct::v1::conv_svc::ConvertResponse;
+use helper::proto::indirect::v1::conv_evt::ConvertedRequest;
+use helper::proto::indirect::v1::indirect_service_server::IndirectService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{delivery_id, get, len, load, reply_id, save_req};
+
+fn converted_req(reply_id: Uuid, delivery_id: Option<Uuid>) -> ConvertedRequest {
+    let now = SystemTime::now();
+    ConvertedRequest {
+        res: Some(ConvertResponse::default()),
+        reply_id: Some(reply_id.into()),
+        received: Some(now.into()),
+        saved: Some(now.into()),
+        loaded: Some(now.into()),
+        delivery_id: delivery_id.map(|d| d.into()),
+    }
+}
+
+#[tokio::test]
+async fn deliveries_are_rejected_without_a_request_buffer() {
+    let res_svc = Arc::new(res_buffer_service_new(16).await);
+    let svc = indirect_service_new(res_svc.clone());
+    let cr = converted_req(Uuid::generate(), Some(Uuid::generate()));
+    let e: Status = svc.converted(Request::new(cr)).await.unwrap_err();
+    assert_eq!(e.code(), Code::FailedPrecondition);
+    assert_eq!(len(res_svc.as_ref()).await, 0);
+}
+
+#[tokio::test]
+async fn deliveries_are_acked_once_saved() {
+    let res_svc = Arc::new(res_buffer_service_new(16).await);
+    let req_svc = Arc::new(acked_request_buffer_service_new(16, Duration::from_secs(60)).await);
+    let svc = acked_indirect_service_new(res_svc.clone(), req_svc.clone());
+
+    let id: Uuid = Uuid::generate();
+    req_svc.save(Request::new(save_req(id))).await.unwrap();
+    let loaded: LoadResponse = load(req_svc.as_ref(), Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(reply_id(&loaded), id);
+
+    let cr = converted_req(id, Some(delivery_id(&loaded)));
+    svc.converted(Request::new(cr)).await.unwrap();
+    get(res_svc.as_ref(), id, Duration::from_millis(10))
+        .await
+        .unwrap();
+
+    let sr = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let stats: StatsResponse = req_svc.stats(Request::new(sr)).await.unwrap().into_inner();
+    assert_eq!(stats.in_flight, 0);
+
+    // not delivered(no ack needed)
+    let cr = converted_req(Uuid::generate(), None);
+    svc.converted(Request::new(cr)).await.unwrap();
+}
+
+#[tokio::test]
+async fn timestamps_are_copied_to_the_response() {
+    let res_svc = Arc::new(res_buffer_service_new(16).await);
+    let svc = indirect_service_new(res_svc.clone());
+
+    let id: Uuid = Uuid::generate();
+    let cr: ConvertedRequest = converted_req(id, None);
+    let sent: ConvertedRequest = cr.clone();
+    svc.converted(Request::new(cr)).await.unwrap();
+    let got: GetResponse = get(res_svc.as_ref(), id, Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(got.received, sent.received);
+    assert_eq!(got.saved, sent.saved);
+    assert_eq!(got.loaded, sent.loaded);
+}
+
+#[tokio::test]
+async fn timestamps_may_be_missing() {
+    let res_svc = Arc::new(res_buffer_service_new(16).await);
+    let svc = indirect_service_new(res_svc.clone());
+
+    // sent by an old worker
+    let id: Uuid = Uuid::generate();
+    let cr = ConvertedRequest {
+        res: Some(ConvertResponse::default()),
+        reply_id: Some(id.into()),
+        ..Default::default()
+    };
+    svc.converted(Request::new(cr)).await.unwrap();
+    let got: GetResponse = get(res_svc.as_ref(), id, Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(got.received, None);
+    assert_eq!(got.saved, None);
+    assert_eq!(got.loaded, None);
+    assert!(got.converted.is_some());
+}
@@ -0,0 +1,66 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use futures::StreamExt;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::retry::Retry;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::vecdeque::svc::{acked_request_buffer_service_new, BufSvcSt};
+use rs_perf_test_helper::indirect::req::get::svc::Buffered;
+
+use helper::proto::buffer::v1::req_buf::AckRequest;
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use helper::proto::indirect::v1::conv_req::{GetRequest, GetResponse};
+use helper::proto::indirect::v1::get_conv_req_service_server::GetConvReqService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::save_req;
+
+#[tokio::test]
+async fn the_delivery_is_forwarded_to_the_worker() {
+    let buf = Arc::new(acked_request_buffer_service_new(4, Duration::from_secs(60)).await);
+    let reply_id: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(reply_id))).await.unwrap();
+
+    let retry = Retry::new(1, Duration::from_millis(1), Duration::from_millis(100));
+    let svc = Buffered::builder()
+        .req_svc(buf.clone())
+        .retry(retry)
+        .build()
+        .unwrap();
+    let req = GetRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let gs = svc.get(Request::new(req)).await.unwrap().into_inner();
+    let got: GetResponse = Box::pin(gs).next().await.unwrap().unwrap();
+    assert_eq!(Uuid::from(got.reply_id.unwrap()), reply_id);
+    assert_eq!(got.delivery_count, 1);
+
+    // the worker acks the delivery it got
+    let ack = AckRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: got.delivery_id,
+    };
+    buf.ack(Request::new(ack)).await.unwrap();
+}
+
+#[tokio::test]
+async fn builders_require_all_the_fields() {
+    let buf = Arc::new(acked_request_buffer_service_new(4, Duration::from_secs(60)).await);
+    let retry = Retry::new(1, Duration::from_millis(1), Duration::from_millis(100));
+
+    let no_req = Buffered::<BufSvcSt>::builder().retry(retry).build();
+    let e: Status = no_req.err().unwrap();
+    assert_eq!(e.code(), Code::InvalidArgument);
+    assert_eq!(e.message(), "request buffer missing");
+
+    let no_retry = Buffered::builder().req_svc(buf).build();
+    let e: Status = no_retry.err().unwrap();
+    assert_eq!(e.code(), Code::InvalidArgument);
+    assert_eq!(e.message(), "retry missing");
+}
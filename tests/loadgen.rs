@@ -0,0 +1,63 @@
+#![cfg(feature = "loadgen")]
+
+use core::time::Duration;
+use std::time::SystemTime;
+
+use rs_perf_test_helper::tonic;
+use tonic::transport::Server;
+
+use rs_perf_test_helper::retry::Retry;
+
+use rs_perf_test_helper::client::convert::ConvertClient;
+use rs_perf_test_helper::convert::buffer::svc::buffered_convert_service_new;
+use rs_perf_test_helper::loadgen::closed::{self, Config, Limit};
+use rs_perf_test_helper::loadgen::report::Report;
+use rs_perf_test_helper::loadgen::seed::Seed;
+
+use helper::proto::direct::v1::convert_service_server::ConvertServiceServer;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{echo, serve};
+
+fn unixtime_us() -> u64 {
+    let d: Duration = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap();
+    d.as_micros() as u64
+}
+
+#[test]
+fn seeds_are_parsed() {
+    let fixed: Seed = "fixed:abc".parse().unwrap();
+    assert_eq!(fixed.generate(), b"abc");
+    let empty: Seed = "fixed:".parse().unwrap();
+    assert!(empty.generate().is_empty());
+    assert!(matches!("unixtime-us".parse(), Ok(Seed::UnixtimeUs)));
+    assert!("unixtime".parse::<Seed>().is_err());
+}
+
+#[test]
+fn unixtime_seeds_are_big_endian_microseconds() {
+    let before: u64 = unixtime_us();
+    let seed: Vec<u8> = Seed::UnixtimeUs.generate();
+    let after: u64 = unixtime_us();
+    let bytes: [u8; 8] = seed.as_slice().try_into().unwrap();
+    let us: u64 = u64::from_be_bytes(bytes);
+    assert!(before <= us && us <= after, "{before} {us} {after}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn closed_loads_send_the_requests_exactly() {
+    let retry = Retry::new(1, Duration::from_millis(1), Duration::from_secs(5));
+    let svc = buffered_convert_service_new(16, 16, retry).await;
+    tokio::spawn(echo(svc.as_req_svc().clone(), svc.as_res_svc().clone()));
+    let url: String = serve(Server::builder().add_service(ConvertServiceServer::new(svc))).await;
+    let client: ConvertClient = ConvertClient::connect(url).await.unwrap();
+
+    let cfg = Config::new(3, Limit::Requests(10), Seed::Fixed(b"seed".to_vec()));
+    let report: Report = closed::run(client, &cfg).await.unwrap();
+    assert_eq!(report.as_samples().len(), 10);
+    assert_eq!(report.ok_count(), 10);
+    assert!(report.latency_at(1.0).is_some());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "loadgen")]
+
+use core::time::Duration;
+
+use rs_perf_test_helper::tonic;
+use tonic::Code;
+
+use rs_perf_test_helper::loadgen::report::{Report, Sample};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// Checks the latency within the precision of the histogram.
+fn near(got: Option<Duration>, want: Duration) -> bool {
+    let got: Duration = got.unwrap();
+    got.abs_diff(want) <= want / 100
+}
+
+#[test]
+fn failed_requests_are_reported_apart() {
+    let mut samples: Vec<Sample> = (1..=100)
+        .map(|i| Sample::new(ms(i), ms(i), Code::Ok))
+        .collect();
+    // timed out
+    samples.extend((0..5).map(|i| Sample::new(ms(i), ms(10_000), Code::DeadlineExceeded)));
+    let r: Report = Report::new(samples, Duration::from_secs(1)).unwrap();
+
+    assert_eq!(r.ok_count(), 100);
+    assert_eq!(r.err_count(), 5);
+    assert!(near(r.latency_at(0.99), ms(99)));
+    assert!(near(r.latency_at(1.0), ms(100)));
+    assert!(near(r.err_latency_at(0.5), ms(10_000)));
+    assert!(r.to_string().contains("err latency: n=5"));
+}
+
+#[test]
+fn no_failures_no_error_latency() {
+    let samples: Vec<Sample> = vec![Sample::new(ms(0), ms(1), Code::Ok)];
+    let r: Report = Report::new(samples, Duration::from_secs(1)).unwrap();
+    assert_eq!(r.err_latency_at(0.5), None);
+    assert!(!r.to_string().contains("err latency"));
+}
+
+#[test]
+fn quantiles_of_successful_requests() {
+    let samples: Vec<Sample> = (1..=100)
+        .rev()
+        .map(|i| Sample::new(ms(100 - i), ms(i), Code::Ok))
+        .collect();
+    let r: Report = Report::new(samples, Duration::from_secs(2)).unwrap();
+    assert!(near(r.latency_at(0.0), ms(1)));
+    assert!(near(r.latency_at(0.5), ms(50)));
+    assert!(near(r.latency_at(0.9), ms(90)));
+    assert!(near(r.latency_at(1.0), ms(100)));
+    assert_eq!(r.throughput(), 50.0);
+    // sorted by the send time
+    assert_eq!(r.as_samples()[0].as_latency(), ms(100));
+}
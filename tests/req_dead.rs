@@ -0,0 +1,150 @@
+use core::time::Duration;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Response, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::actor::{ActorConfig, FullPolicy};
+use rs_perf_test_helper::buffer::vecdeque::dead::dead_lettered_request_buffer_service_new;
+use rs_perf_test_helper::buffer::vecdeque::dead::dead_lettered_request_buffer_service_with_config;
+
+use helper::proto::buffer::v1::dead_letter::{ListRequest, RequeueRequest};
+use helper::proto::buffer::v1::dead_letter_service_server::DeadLetterService;
+use helper::proto::buffer::v1::req_buf::{LoadResponse, RejectRequest, RejectResponse};
+use helper::proto::buffer::v1::req_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{load, save_req};
+
+fn list_req() -> ListRequest {
+    ListRequest {
+        request_id: Some(Uuid::generate().into()),
+        max_count: 0,
+    }
+}
+
+#[tokio::test]
+async fn exhausted_requests_are_dead_lettered() {
+    let (buf, dead) =
+        dead_lettered_request_buffer_service_new(16, Duration::from_millis(10), 2, 16).await;
+    let id: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(id))).await.unwrap();
+
+    load(&buf, Duration::from_millis(10)).await.unwrap();
+    load(&buf, Duration::from_secs(1)).await.unwrap();
+    let gone: Status = load(&buf, Duration::from_millis(50)).await.unwrap_err();
+    assert_eq!(gone.code(), Code::DeadlineExceeded);
+
+    let listed = dead
+        .list(Request::new(list_req()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.entries.len(), 1);
+    assert_eq!(listed.entries[0].delivery_count, 2);
+    assert_eq!(listed.entries[0].reply_id.clone().map(Uuid::from), Some(id));
+
+    let rr = RequeueRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(id.into()),
+    };
+    dead.requeue(Request::new(rr)).await.unwrap();
+    let again: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    assert_eq!(again.delivery_count, 1);
+    let listed = dead
+        .list(Request::new(list_req()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(listed.entries.is_empty());
+}
+
+#[tokio::test]
+async fn rejected_requests_are_dead_lettered() {
+    let (buf, dead) =
+        dead_lettered_request_buffer_service_new(16, Duration::from_secs(60), 5, 16).await;
+    buf.save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap();
+
+    let loaded: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    let rr = RejectRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: loaded.delivery_id,
+        reason: "invalid seed".into(),
+    };
+    buf.reject(Request::new(rr)).await.unwrap();
+
+    let listed = dead
+        .list(Request::new(list_req()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.entries.len(), 1);
+    assert_eq!(listed.entries[0].reason, "invalid seed");
+    load(&dead.buffer(), Duration::from_millis(10))
+        .await
+        .unwrap();
+}
+
+async fn reject_twice<B>(buf: &B) -> Vec<Result<Response<RejectResponse>, Status>>
+where
+    B: ReqBufferService,
+{
+    for _ in 0..2 {
+        buf.save(Request::new(save_req(Uuid::generate())))
+            .await
+            .unwrap();
+    }
+
+    let mut rejected: Vec<Result<_, Status>> = vec![];
+    for _ in 0..2 {
+        let loaded: LoadResponse = load(buf, Duration::from_millis(10)).await.unwrap();
+        let rr = RejectRequest {
+            request_id: Some(Uuid::generate().into()),
+            delivery_id: loaded.delivery_id,
+            reason: "invalid seed".into(),
+        };
+        rejected.push(buf.reject(Request::new(rr)).await);
+    }
+    rejected
+}
+
+#[tokio::test]
+async fn requests_dropped_by_a_full_dead_letter_buffer_are_reported() {
+    let (buf, _dead) =
+        dead_lettered_request_buffer_service_new(16, Duration::from_secs(60), 5, 1).await;
+    let rejected = reject_twice(&buf).await;
+    assert!(rejected[0].is_ok());
+    let dropped: &Status = rejected[1].as_ref().unwrap_err();
+    assert_eq!(dropped.code(), Code::DataLoss);
+
+    let sr = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let stats: StatsResponse = buf.stats(Request::new(sr)).await.unwrap().into_inner();
+    assert_eq!(stats.dropped, 1);
+    assert_eq!(stats.in_flight, 0);
+}
+
+#[tokio::test]
+async fn the_dead_letter_buffer_rejects_whatever_the_policy() {
+    let cfg = ActorConfig::new(1, FullPolicy::DropOldest);
+    let (buf, dead) =
+        dead_lettered_request_buffer_service_with_config(&cfg, 16, Duration::from_secs(60), 5, 1)
+            .await;
+    let rejected = reject_twice(&buf).await;
+    assert!(rejected[0].is_ok());
+    let dropped: &Status = rejected[1].as_ref().unwrap_err();
+    assert_eq!(dropped.code(), Code::DataLoss);
+
+    let listed = dead
+        .list(Request::new(list_req()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.entries.len(), 1);
+}
@@ -0,0 +1,186 @@
+use core::time::Duration;
+use std::io::Write;
+use std::path::PathBuf;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::retry::Retry;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::actor::{ActorConfig, FullPolicy};
+use rs_perf_test_helper::buffer::file::svc::file_request_buffer_service_new;
+use rs_perf_test_helper::buffer::file::svc::file_request_buffer_service_with_config;
+
+use helper::proto::buffer::v1::req_buf::{AckRequest, LoadRequest};
+use helper::proto::buffer::v1::req_buf::{NackRequest, RejectRequest};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{load, load_many, reply_id, save_req};
+
+fn wal_path() -> PathBuf {
+    std::env::temp_dir().join(format!("req-wal-{}.bin", Uuid::generate()))
+}
+
+#[tokio::test]
+async fn queued_requests_survive_restarts() {
+    let path: PathBuf = wal_path();
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::generate()).collect();
+    {
+        let buf = file_request_buffer_service_new(&path, 16, 2).await.unwrap();
+        for id in &ids {
+            buf.save(Request::new(save_req(*id))).await.unwrap();
+        }
+        assert_eq!(
+            reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+            ids[0]
+        );
+    }
+
+    let buf = file_request_buffer_service_new(&path, 16, 2).await.unwrap();
+    assert_eq!(
+        reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+        ids[1]
+    );
+    assert_eq!(
+        reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+        ids[2]
+    );
+    let empty: Status = load(&buf, Duration::from_millis(10)).await.unwrap_err();
+    assert_eq!(empty.code(), Code::DeadlineExceeded);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn torn_tails_are_ignored() {
+    let path: PathBuf = wal_path();
+    let id: Uuid = Uuid::generate();
+    {
+        let buf = file_request_buffer_service_new(&path, 16, 100)
+            .await
+            .unwrap();
+        buf.save(Request::new(save_req(id))).await.unwrap();
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    f.write_all(&[0x7f, 0x01]).unwrap();
+
+    let buf = file_request_buffer_service_new(&path, 16, 100)
+        .await
+        .unwrap();
+    assert_eq!(
+        reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+        id
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn requests_loaded_by_a_gone_client_are_kept() {
+    let path: PathBuf = wal_path();
+    let id: Uuid = Uuid::generate();
+    {
+        let buf = file_request_buffer_service_new(&path, 16, 100)
+            .await
+            .unwrap();
+        let retry = Retry::new(1, Duration::from_secs(1), Duration::from_secs(1));
+        let req = LoadRequest {
+            request_id: Some(Uuid::generate().into()),
+            retry: Some((&retry).into()),
+            max_count: 1,
+            window: 1,
+        };
+        // the client goes away before the request is saved
+        drop(buf.load(Request::new(req)).await.unwrap());
+        buf.save(Request::new(save_req(id))).await.unwrap();
+
+        assert_eq!(
+            reply_id(&load(&buf, Duration::from_secs(1)).await.unwrap()),
+            id
+        );
+    }
+
+    // logged as loaded once sent
+    let buf = file_request_buffer_service_new(&path, 16, 100)
+        .await
+        .unwrap();
+    let none: Status = load(&buf, Duration::from_millis(10)).await.unwrap_err();
+    assert_eq!(none.code(), Code::DeadlineExceeded);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn requests_put_back_keep_the_order() {
+    let path: PathBuf = wal_path();
+    let buf = file_request_buffer_service_new(&path, 16, 100)
+        .await
+        .unwrap();
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::generate()).collect();
+    for id in &ids {
+        buf.save(Request::new(save_req(*id))).await.unwrap();
+    }
+    // the client goes away after the first two requests are loaded
+    drop(load_many(&buf, Duration::from_secs(1), 2).await.unwrap());
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    for id in &ids {
+        assert_eq!(
+            reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+            *id
+        );
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn full_buffers_reject_whatever_the_policy() {
+    let path: PathBuf = wal_path();
+    let cfg = ActorConfig::new(1, FullPolicy::DropOldest);
+    let buf = file_request_buffer_service_with_config(&cfg, &path, 1, 100)
+        .await
+        .unwrap();
+    let first: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(first))).await.unwrap();
+    let full: Status = buf
+        .save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    assert_eq!(full.code(), Code::Unavailable);
+    assert_eq!(
+        reply_id(&load(&buf, Duration::from_millis(10)).await.unwrap()),
+        first
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn acks_are_unimplemented() {
+    let path: PathBuf = wal_path();
+    let buf = file_request_buffer_service_new(&path, 16, 100)
+        .await
+        .unwrap();
+    let ar = AckRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(Uuid::generate().into()),
+    };
+    let e: Status = buf.ack(Request::new(ar)).await.unwrap_err();
+    assert_eq!(e.code(), Code::Unimplemented);
+    let nr = NackRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(Uuid::generate().into()),
+    };
+    let e: Status = buf.nack(Request::new(nr)).await.unwrap_err();
+    assert_eq!(e.code(), Code::Unimplemented);
+    let rr = RejectRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(Uuid::generate().into()),
+        reason: "invalid seed".into(),
+    };
+    let e: Status = buf.reject(Request::new(rr)).await.unwrap_err();
+    assert_eq!(e.code(), Code::Unimplemented);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,176 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use futures::StreamExt;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::retry::Retry;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::vecdeque::svc::{
+    acked_request_buffer_service_new, request_buffer_service_new,
+};
+
+use helper::proto::buffer::v1::req_buf::{AckRequest, NackRequest};
+use helper::proto::buffer::v1::req_buf::{LoadRequest, LoadResponse};
+use helper::proto::buffer::v1::req_buffer_service_server::ReqBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{delivery_id, load, load_many, reply_id, save_req};
+
+#[tokio::test]
+async fn waiting_loads_are_served_in_order() {
+    let buf = request_buffer_service_new(16).await;
+    let first: Uuid = Uuid::generate();
+    let second: Uuid = Uuid::generate();
+    let started: Instant = Instant::now();
+    let (l1, l2, saved) = tokio::join!(
+        load(&buf, Duration::from_secs(10)),
+        async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            load(&buf, Duration::from_secs(10)).await
+        },
+        async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            buf.save(Request::new(save_req(first))).await?;
+            buf.save(Request::new(save_req(second))).await
+        },
+    );
+    saved.unwrap();
+    assert_eq!(reply_id(&l1.unwrap()), first);
+    assert_eq!(reply_id(&l2.unwrap()), second);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn requests_saved_after_timeout_are_kept() {
+    let buf = request_buffer_service_new(16).await;
+    let timedout: Status = load(&buf, Duration::from_millis(10)).await.unwrap_err();
+    assert_eq!(timedout.code(), Code::DeadlineExceeded);
+
+    let id: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(id))).await.unwrap();
+    let loaded: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    assert_eq!(reply_id(&loaded), id);
+    assert!(loaded.loaded.is_some());
+}
+
+#[tokio::test]
+async fn streaming_load_ends_when_idle() {
+    let buf = request_buffer_service_new(16).await;
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::generate()).collect();
+    for id in &ids {
+        buf.save(Request::new(save_req(*id))).await.unwrap();
+    }
+    let ls = load_many(&buf, Duration::from_millis(20), 5).await.unwrap();
+    let loaded: Vec<Uuid> = Box::pin(ls)
+        .map(|r: Result<LoadResponse, Status>| reply_id(&r.unwrap()))
+        .collect()
+        .await;
+    assert_eq!(loaded, ids);
+}
+
+#[tokio::test]
+async fn streaming_load_stops_at_max_count() {
+    let buf = request_buffer_service_new(16).await;
+    for _ in 0..3 {
+        buf.save(Request::new(save_req(Uuid::generate())))
+            .await
+            .unwrap();
+    }
+    let ls = load_many(&buf, Duration::from_secs(10), 2).await.unwrap();
+    let loaded: Vec<Result<LoadResponse, Status>> = Box::pin(ls).collect().await;
+    assert_eq!(loaded.len(), 2);
+    load(&buf, Duration::from_millis(10)).await.unwrap();
+}
+
+#[tokio::test]
+async fn unacked_requests_are_redelivered() {
+    let buf = acked_request_buffer_service_new(16, Duration::from_millis(20)).await;
+    let id: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(id))).await.unwrap();
+
+    let first: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    assert_eq!(first.delivery_count, 1);
+    let second: LoadResponse = load(&buf, Duration::from_secs(1)).await.unwrap();
+    assert_eq!(reply_id(&second), id);
+    assert_eq!(second.delivery_count, 2);
+    assert_ne!(delivery_id(&first), delivery_id(&second));
+
+    let ack = AckRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(delivery_id(&second).into()),
+    };
+    buf.ack(Request::new(ack)).await.unwrap();
+    let none: Status = load(&buf, Duration::from_millis(50)).await.unwrap_err();
+    assert_eq!(none.code(), Code::DeadlineExceeded);
+
+    let stale = AckRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(delivery_id(&first).into()),
+    };
+    let stale: Status = buf.ack(Request::new(stale)).await.unwrap_err();
+    assert_eq!(stale.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn nacked_requests_are_redelivered_at_once() {
+    let buf = acked_request_buffer_service_new(16, Duration::from_secs(60)).await;
+    let id: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(id))).await.unwrap();
+
+    let first: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    let nack = NackRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(delivery_id(&first).into()),
+    };
+    buf.nack(Request::new(nack)).await.unwrap();
+    let second: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+    assert_eq!(reply_id(&second), id);
+    assert_eq!(second.delivery_count, 2);
+}
+
+#[tokio::test]
+async fn huge_windows_are_capped() {
+    let buf = request_buffer_service_new(4).await;
+    let first: Uuid = Uuid::generate();
+    buf.save(Request::new(save_req(first))).await.unwrap();
+
+    let retry = Retry::new(1, Duration::from_secs(1), Duration::from_millis(10));
+    let req = LoadRequest {
+        request_id: Some(Uuid::generate().into()),
+        retry: Some((&retry).into()),
+        max_count: u64::MAX,
+        window: u64::MAX,
+    };
+    let ls = buf.load(Request::new(req)).await.unwrap().into_inner();
+    let mut ls = Box::pin(ls);
+    assert_eq!(reply_id(&ls.next().await.unwrap().unwrap()), first);
+}
+
+#[tokio::test]
+async fn unacked_requests_count_against_max_size() {
+    let buf = acked_request_buffer_service_new(1, Duration::from_secs(60)).await;
+    buf.save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap();
+    let first: LoadResponse = load(&buf, Duration::from_millis(10)).await.unwrap();
+
+    let full: Status = buf
+        .save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap_err();
+    assert_eq!(full.code(), Code::Unavailable);
+
+    let ack = AckRequest {
+        request_id: Some(Uuid::generate().into()),
+        delivery_id: Some(delivery_id(&first).into()),
+    };
+    buf.ack(Request::new(ack)).await.unwrap();
+    buf.save(Request::new(save_req(Uuid::generate())))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,168 @@
+use core::time::Duration;
+use std::time::{Instant, SystemTime};
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::buffer::res::store::{MemStore, Store};
+
+use helper::proto::buffer::v1::res_buf::{GetResponse, StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{get, len, set_req};
+
+async fn stats<B>(b: &B) -> StatsResponse
+where
+    B: ResBufferService,
+{
+    let req = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    b.stats(Request::new(req)).await.unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn waiting_get_is_woken_by_set() {
+    let buf = res_buffer_service_new(16).await;
+    let reply_id: Uuid = Uuid::generate();
+    let started: Instant = Instant::now();
+    let (got, set) = tokio::join!(get(&buf, reply_id, Duration::from_secs(10)), async {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        buf.set(Request::new(set_req(reply_id))).await
+    });
+    set.unwrap();
+    got.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn late_responses_are_kept() {
+    let buf = res_buffer_service_new(16).await;
+    let reply_id: Uuid = Uuid::generate();
+    let timedout: Status = get(&buf, reply_id, Duration::from_millis(10))
+        .await
+        .unwrap_err();
+    assert_eq!(timedout.code(), Code::DeadlineExceeded);
+
+    buf.set(Request::new(set_req(reply_id))).await.unwrap();
+    get(&buf, reply_id, Duration::from_millis(10))
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn responses_set_at_the_timeout_are_not_lost() {
+    let buf = res_buffer_service_new(16).await;
+    let timeout = Duration::from_millis(1);
+    for _ in 0..200 {
+        let reply_id: Uuid = Uuid::generate();
+        let (got, set) = tokio::join!(get(&buf, reply_id, timeout), async {
+            tokio::time::sleep(timeout).await;
+            buf.set(Request::new(set_req(reply_id))).await
+        });
+        set.unwrap();
+        // either the get took the response or the response is still kept
+        if got.is_err() {
+            get(&buf, reply_id, Duration::from_secs(1)).await.unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn concurrent_gets_take_the_response_once() {
+    let buf = res_buffer_service_new(16).await;
+    let reply_id: Uuid = Uuid::generate();
+    let timeout = Duration::from_millis(200);
+    let (first, second, set) = tokio::join!(
+        get(&buf, reply_id, timeout),
+        async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            get(&buf, reply_id, timeout).await
+        },
+        async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            buf.set(Request::new(set_req(reply_id))).await
+        }
+    );
+    set.unwrap();
+    first.unwrap();
+    assert_eq!(second.unwrap_err().code(), Code::DeadlineExceeded);
+}
+
+fn set_at(set: SystemTime) -> GetResponse {
+    GetResponse {
+        set: Some(set.into()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn responses_without_the_set_time_are_evicted_last() {
+    let (unset, set) = (Uuid::generate(), Uuid::generate());
+    let mut ms = MemStore::default();
+    ms.insert(unset, GetResponse::default()).await.unwrap();
+    ms.insert(set, set_at(SystemTime::now())).await.unwrap();
+    assert_eq!(ms.oldest(), Some(set));
+
+    ms.remove(&set).await.unwrap();
+    assert_eq!(ms.oldest(), Some(unset));
+    assert_eq!(ms.oldest_set(), None);
+}
+
+#[tokio::test]
+async fn responses_are_ordered_by_the_set_time() {
+    let now: SystemTime = SystemTime::now();
+    let times: Vec<SystemTime> = (0..3).map(|i| now + Duration::from_secs(i)).collect();
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::generate()).collect();
+    let mut ms = MemStore::default();
+    for ix in [2, 0, 1] {
+        ms.insert(ids[ix], set_at(times[ix])).await.unwrap();
+    }
+    for ix in 0..3 {
+        assert_eq!(ms.oldest(), Some(ids[ix]));
+        assert_eq!(ms.oldest_set(), Some(times[ix]));
+        ms.remove(&ids[ix]).await.unwrap().unwrap();
+    }
+    assert_eq!(ms.oldest(), None);
+}
+
+#[tokio::test]
+async fn len_counts_the_kept_responses() {
+    let buf = res_buffer_service_new(16).await;
+    assert_eq!(len(&buf).await, 0);
+
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::generate()).collect();
+    for id in &ids {
+        buf.set(Request::new(set_req(*id))).await.unwrap();
+    }
+    assert_eq!(len(&buf).await, 3);
+
+    get(&buf, ids[0], Duration::from_millis(10)).await.unwrap();
+    assert_eq!(len(&buf).await, 2);
+}
+
+#[tokio::test]
+async fn stats_report_the_capacity_and_the_oldest_response() {
+    let buf = res_buffer_service_new(16).await;
+    let empty: StatsResponse = stats(&buf).await;
+    assert_eq!(empty.capacity, 16);
+    assert_eq!(empty.pending, 0);
+    assert_eq!(empty.oldest_age, None);
+
+    buf.set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    buf.set(Request::new(set_req(Uuid::generate())))
+        .await
+        .unwrap();
+    let two: StatsResponse = stats(&buf).await;
+    assert_eq!(two.pending, 2);
+    let oldest: Duration = two.oldest_age.unwrap().try_into().unwrap();
+    assert!(oldest >= Duration::from_millis(20));
+}
@@ -0,0 +1,84 @@
+use core::time::Duration;
+use std::path::PathBuf;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::buffer::res::file::svc::file_res_buffer_service_new;
+
+use helper::proto::buffer::v1::res_buf::{DelRequest, GetResponse, SetRequest};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use helper::proto::direct::v1::conv_svc::ConvertResponse;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{get, set_req};
+
+// the responses got are already set; the removal is written to the log before the reply
+const GET_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn generated_set_req(reply_id: Uuid, generated: Vec<u8>) -> SetRequest {
+    let mut sr: SetRequest = set_req(reply_id);
+    sr.res = Some(ConvertResponse {
+        converted: None,
+        generated,
+    });
+    sr
+}
+
+fn del_req(reply_id: Uuid) -> DelRequest {
+    DelRequest {
+        request_id: Some(Uuid::generate().into()),
+        reply_id: Some(reply_id.into()),
+    }
+}
+
+fn log_path() -> PathBuf {
+    std::env::temp_dir().join(format!("res-log-{}.bin", Uuid::generate()))
+}
+
+#[tokio::test]
+async fn responses_survive_restarts() {
+    let path: PathBuf = log_path();
+    let (got, kept) = (Uuid::generate(), Uuid::generate());
+    {
+        let buf = file_res_buffer_service_new(&path, 16, 1).await.unwrap();
+        buf.set(Request::new(generated_set_req(got, vec![1])))
+            .await
+            .unwrap();
+        buf.set(Request::new(generated_set_req(kept, vec![2])))
+            .await
+            .unwrap();
+        get(&buf, got, GET_TIMEOUT).await.unwrap();
+    }
+
+    let buf = file_res_buffer_service_new(&path, 16, 1).await.unwrap();
+    let gr: GetResponse = get(&buf, kept, GET_TIMEOUT).await.unwrap();
+    assert_eq!(gr.res.unwrap().generated, vec![2]);
+    assert!(gr.set.is_some());
+    let gone: Status = buf.del(Request::new(del_req(got))).await.unwrap_err();
+    assert_eq!(gone.code(), Code::NotFound);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn duplicates_are_rejected() {
+    let path: PathBuf = log_path();
+    let reply_id: Uuid = Uuid::generate();
+    {
+        let buf = file_res_buffer_service_new(&path, 16, 100).await.unwrap();
+        buf.set(Request::new(generated_set_req(reply_id, vec![])))
+            .await
+            .unwrap();
+    }
+    let buf = file_res_buffer_service_new(&path, 16, 100).await.unwrap();
+    let dup: Status = buf
+        .set(Request::new(generated_set_req(reply_id, vec![])))
+        .await
+        .unwrap_err();
+    assert_eq!(dup.code(), Code::AlreadyExists);
+    buf.del(Request::new(del_req(reply_id))).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,110 @@
+use core::time::Duration;
+use std::sync::Arc;
+use std::time::Instant;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::buffer::full::Full;
+use rs_perf_test_helper::uuid::Uuid;
+
+use rs_perf_test_helper::actor::{ActorConfig, FullPolicy};
+use rs_perf_test_helper::buffer::res::sharded::svc::sharded_res_buffer_service_new;
+use rs_perf_test_helper::buffer::res::sharded::svc::sharded_res_buffer_service_with_config;
+
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+mod common;
+use common::{get, len, set_req};
+
+const GET_TIMEOUT: Duration = Duration::from_millis(10);
+
+#[tokio::test]
+async fn responses_are_routed_by_reply_id() {
+    let buf = sharded_res_buffer_service_new(4, 64).await.unwrap();
+    let ids: Vec<Uuid> = (0..16).map(|_| Uuid::generate()).collect();
+    for id in &ids {
+        buf.set(Request::new(set_req(*id))).await.unwrap();
+    }
+    let dup: Status = buf.set(Request::new(set_req(ids[0]))).await.unwrap_err();
+    assert_eq!(dup.code(), Code::AlreadyExists);
+    assert_eq!(len(&buf).await, 16);
+
+    for id in &ids {
+        get(&buf, *id, GET_TIMEOUT).await.unwrap();
+    }
+    assert_eq!(len(&buf).await, 0);
+
+    let missing: Status = get(&buf, ids[0], GET_TIMEOUT).await.unwrap_err();
+    assert_eq!(missing.code(), Code::DeadlineExceeded);
+}
+
+#[tokio::test]
+async fn no_shards_are_rejected() {
+    let e: Status = sharded_res_buffer_service_new(0, 64).await.err().unwrap();
+    assert_eq!(e.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn max_size_is_shared_by_the_shards() {
+    let buf = sharded_res_buffer_service_new(4, 10).await.unwrap();
+    let req = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let stats: StatsResponse = buf.stats(Request::new(req)).await.unwrap().into_inner();
+    assert_eq!(stats.capacity, 10);
+
+    // more shards than responses
+    let buf = sharded_res_buffer_service_new(4, 3).await.unwrap();
+    let mut kept: Vec<Uuid> = vec![];
+    for _ in 0..32 {
+        let id: Uuid = Uuid::generate();
+        match buf.set(Request::new(set_req(id))).await {
+            Ok(_) => kept.push(id),
+            Err(e) => {
+                let f: Full = Full::from_status(&e).unwrap();
+                assert_eq!(f.as_limit(), 3);
+                assert_eq!(f.as_size(), 3);
+            }
+        }
+    }
+    assert_eq!(kept.len(), 3);
+
+    // a get from any shard makes room for the others
+    get(&buf, kept[0], GET_TIMEOUT).await.unwrap();
+    let mut set: u64 = 0;
+    for _ in 0..32 {
+        if buf
+            .set(Request::new(set_req(Uuid::generate())))
+            .await
+            .is_ok()
+        {
+            set += 1;
+        }
+    }
+    assert_eq!(set, 1);
+}
+
+#[tokio::test]
+async fn blocked_sets_wait_for_room_in_any_shard() {
+    let cfg = ActorConfig::new(1, FullPolicy::Block(Duration::from_secs(10)));
+    let buf = sharded_res_buffer_service_with_config(&cfg, 8, 1)
+        .await
+        .unwrap();
+    let buf = Arc::new(buf);
+    let first: Uuid = Uuid::generate();
+    buf.set(Request::new(set_req(first))).await.unwrap();
+
+    let started: Instant = Instant::now();
+    let blocked = {
+        let buf = buf.clone();
+        tokio::spawn(async move { buf.set(Request::new(set_req(Uuid::generate()))).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    get(buf.as_ref(), first, GET_TIMEOUT).await.unwrap();
+
+    blocked.await.unwrap().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
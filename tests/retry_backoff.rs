@@ -0,0 +1,92 @@
+use core::time::Duration;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Status};
+
+use rs_perf_test_helper::retry::backoff::Backoff;
+use rs_perf_test_helper::retry::{exec, Retry};
+
+use helper::proto::common::v1::Retry as Gretry;
+use rs_perf_test_helper::rpc::perf::helper;
+
+const INTERVAL: Duration = Duration::from_millis(1);
+const CAP: Duration = Duration::from_millis(10);
+
+fn retry(retry_max: u64, backoff: Backoff) -> Retry {
+    Retry::new(retry_max, INTERVAL, Duration::from_secs(1)).backoff(backoff)
+}
+
+#[test]
+fn fixed_delays_keep_the_interval() {
+    let delays: Vec<Duration> = retry(3, Backoff::Fixed).delays().take(3).collect();
+    assert_eq!(delays, vec![INTERVAL; 3]);
+}
+
+#[test]
+fn exponential_delays_grow_up_to_the_cap() {
+    let r: Retry = retry(3, Backoff::exponential(2.0, CAP));
+    let delays: Vec<Duration> = r.delays().take(6).collect();
+    let ms: Vec<Duration> = [1, 2, 4, 8, 10, 10]
+        .into_iter()
+        .map(Duration::from_millis)
+        .collect();
+    assert_eq!(delays, ms);
+}
+
+#[test]
+fn jittered_delays_stay_in_range_and_differ() {
+    let r: Retry = retry(3, Backoff::decorrelated_jitter(CAP));
+    let a: Vec<Duration> = r.delays().take(64).collect();
+    let b: Vec<Duration> = r.delays().take(64).collect();
+    assert!(a.iter().all(|d| INTERVAL <= *d && *d <= CAP));
+    assert_ne!(a, b);
+}
+
+#[test]
+fn backoff_survives_the_proto() {
+    let r: Retry = retry(3, Backoff::exponential(1.5, CAP));
+    let g: Gretry = (&r).into();
+    let back: Retry = (&g).into();
+    assert_eq!(back.as_backoff(), r.as_backoff());
+
+    let missing = Gretry { backoff: None, ..g };
+    assert_eq!(Retry::from(&missing).as_backoff(), Backoff::Fixed);
+}
+
+#[tokio::test]
+async fn transient_errors_are_retried_up_to_the_max() {
+    let r: Retry = retry(2, Backoff::exponential(2.0, CAP));
+    let mut attempts: Vec<u64> = vec![];
+    let e: Status = exec::run(&r, exec::is_transient, |attempt: u64| {
+        attempts.push(attempt);
+        async { Err::<(), _>(Status::unavailable("full")) }
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(e.code(), Code::Unavailable);
+    assert_eq!(attempts, vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn retries_stop_at_success_or_permanent_errors() {
+    let r: Retry = retry(5, Backoff::Fixed);
+    let got: u64 = exec::run(&r, exec::is_transient, |attempt: u64| async move {
+        match attempt {
+            0 => Err(Status::deadline_exceeded("not yet")),
+            n => Ok(n),
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(got, 1);
+
+    let mut count: u64 = 0;
+    let e: Status = exec::run(&r, exec::is_transient, |_| {
+        count += 1;
+        async { Err::<(), _>(Status::invalid_argument("bad")) }
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(e.code(), Code::InvalidArgument);
+    assert_eq!(count, 1);
+}
@@ -0,0 +1,131 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Response, Status};
+
+use rs_perf_test_helper::retry::budget::Budget;
+use rs_perf_test_helper::retry::layer::{attempts, Retrying};
+use rs_perf_test_helper::retry::{exec, Retry};
+use rs_perf_test_helper::stats::retry::RetryCounts;
+
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+/// Always rejects like a saturated buffer.
+struct Saturated;
+
+#[tonic::async_trait]
+impl ConvertService for Saturated {
+    async fn convert(
+        &self,
+        _req: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        Err(Status::unavailable("too many requests"))
+    }
+}
+
+fn retry(retry_max: u64) -> Retry {
+    Retry::new(retry_max, Duration::from_micros(10), Duration::from_secs(1))
+}
+
+#[test]
+fn tokens_run_out() {
+    let b = Budget::new(2, 0.0);
+    assert!(b.try_retry());
+    assert!(b.try_retry());
+    assert!(!b.try_retry());
+
+    let c: RetryCounts = b.snapshot();
+    assert_eq!((c.as_first(), c.as_retries(), c.as_denied()), (0, 2, 1));
+}
+
+#[test]
+fn first_attempts_refill_up_to_the_max() {
+    let b = Budget::new(1, 0.5);
+    assert!(b.try_retry());
+    b.on_first();
+    assert!(!b.try_retry());
+    b.on_first();
+    assert!(b.try_retry());
+
+    for _ in 0..10 {
+        b.on_first();
+    }
+    assert_eq!(b.as_tokens(), 1.0);
+}
+
+#[test]
+fn amplification_counts_retries_per_first_attempt() {
+    let b = Budget::unlimited();
+    for _ in 0..4 {
+        b.on_first();
+    }
+    assert!(b.try_retry());
+    assert!(b.try_retry());
+
+    let c: RetryCounts = b.snapshot();
+    assert_eq!(c.amplification(), 1.5);
+    assert_eq!(c.as_denied(), 0);
+    assert_eq!(
+        c.to_string(),
+        "first: 4, retries: 2, denied: 0, amplification: 1.500"
+    );
+    assert_eq!(RetryCounts::default().amplification(), 1.0);
+}
+
+#[tokio::test]
+async fn the_executor_stops_when_the_budget_ran_out() {
+    let b = Budget::new(1, 0.0);
+    let mut calls: u64 = 0;
+    let e: Status = exec::run_budgeted(&retry(5), None, &b, exec::is_transient, |_| {
+        calls += 1;
+        async { Err::<(), _>(Status::unavailable("full")) }
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(e.code(), Code::Unavailable);
+    assert_eq!(calls, 2);
+    assert_eq!(b.snapshot().as_denied(), 1);
+}
+
+#[tokio::test]
+async fn the_steps_of_a_request_count_one_first_attempt() {
+    let b = Budget::new(10, 0.0);
+    let scope = exec::Scope::new(&b);
+    for _ in 0..2 {
+        let e: Status = scope
+            .run_until(&retry(1), None, exec::is_transient, |_| async {
+                Err::<(), _>(Status::unavailable("full"))
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(e.code(), Code::Unavailable);
+    }
+    let c: RetryCounts = b.snapshot();
+    assert_eq!(c.as_first(), 1);
+    assert_eq!(c.as_retries(), 2);
+}
+
+#[tokio::test]
+async fn services_share_the_budget() {
+    let b: Arc<Budget> = Arc::new(Budget::new(3, 0.0));
+    let first = Retrying::new(Saturated, retry(10)).budget(b.clone());
+    let second = Retrying::new(Saturated, retry(10)).budget(b.clone());
+
+    let e1: Status = first
+        .convert(Request::new(ConvertRequest::default()))
+        .await
+        .unwrap_err();
+    let e2: Status = second
+        .convert(Request::new(ConvertRequest::default()))
+        .await
+        .unwrap_err();
+    assert_eq!(attempts(e1.metadata()), Some(4));
+    assert_eq!(attempts(e2.metadata()), Some(1));
+
+    let c: RetryCounts = b.snapshot();
+    assert_eq!((c.as_first(), c.as_retries(), c.as_denied()), (2, 3, 2));
+    assert_eq!(c.amplification(), 2.5);
+}
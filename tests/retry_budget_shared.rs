@@ -0,0 +1,40 @@
+use core::time::Duration;
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Status};
+
+use rs_perf_test_helper::buffer::res::btree::svc::res_buffer_service_new;
+use rs_perf_test_helper::retry::budget::{self, Budget};
+use rs_perf_test_helper::retry::{exec, Retry};
+use rs_perf_test_helper::stats::retry::RetryCounts;
+use rs_perf_test_helper::uuid::Uuid;
+
+use helper::proto::buffer::v1::res_buf::{StatsRequest, StatsResponse};
+use helper::proto::buffer::v1::res_buffer_service_server::ResBufferService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+// the only test of this binary: the shared budget is set once per process
+#[tokio::test]
+async fn the_installed_budget_is_shared() {
+    assert!(budget::install(Budget::new(1, 0.0)).is_ok());
+    assert!(budget::install(Budget::unlimited()).is_err());
+
+    let retry = Retry::new(5, Duration::from_micros(10), Duration::from_secs(1));
+    let e: Status = exec::run(&retry, exec::is_transient, |_| async {
+        Err::<(), _>(Status::unavailable("full"))
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(e.code(), Code::Unavailable);
+
+    let buf = res_buffer_service_new(4).await;
+    let req = StatsRequest {
+        request_id: Some(Uuid::generate().into()),
+    };
+    let stats: StatsResponse = buf.stats(Request::new(req)).await.unwrap().into_inner();
+    let c: RetryCounts = stats.retries.as_ref().unwrap().into();
+    assert_eq!(c.as_first(), 1);
+    assert_eq!(c.as_retries(), 1);
+    assert_eq!(c.as_denied(), 1);
+    assert_eq!(c, budget::shared().snapshot());
+}
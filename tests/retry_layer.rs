@@ -0,0 +1,94 @@
+use core::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rs_perf_test_helper::tonic;
+use tonic::{Code, Request, Response, Status};
+
+use rs_perf_test_helper::retry::backoff::Backoff;
+use rs_perf_test_helper::retry::layer::{attempts, Retrying};
+use rs_perf_test_helper::retry::Retry;
+
+use helper::proto::direct::v1::conv_svc::{ConvertRequest, ConvertResponse};
+use helper::proto::direct::v1::convert_service_server::ConvertService;
+use rs_perf_test_helper::rpc::perf::helper;
+
+/// Fails with the code until the number of failures.
+struct Flaky {
+    code: Code,
+    failures: u64,
+    calls: AtomicU64,
+}
+
+impl Flaky {
+    fn new(code: Code, failures: u64) -> Self {
+        Self {
+            code,
+            failures,
+            calls: AtomicU64::new(0),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ConvertService for Flaky {
+    async fn convert(
+        &self,
+        _req: Request<ConvertRequest>,
+    ) -> Result<Response<ConvertResponse>, Status> {
+        let calls: u64 = self.calls.fetch_add(1, Ordering::SeqCst);
+        match calls < self.failures {
+            true => Err(Status::new(self.code, "flaky")),
+            false => Ok(Response::new(ConvertResponse::default())),
+        }
+    }
+}
+
+fn retry(retry_max: u64) -> Retry {
+    let r = Retry::new(retry_max, Duration::from_millis(1), Duration::from_secs(1));
+    r.backoff(Backoff::exponential(2.0, Duration::from_millis(4)))
+}
+
+fn req() -> Request<ConvertRequest> {
+    Request::new(ConvertRequest::default())
+}
+
+#[tokio::test]
+async fn default_codes_are_retried() {
+    for code in [
+        Code::Unavailable,
+        Code::ResourceExhausted,
+        Code::DeadlineExceeded,
+    ] {
+        let svc = Retrying::new(Flaky::new(code, 2), retry(3));
+        let res: Response<ConvertResponse> = svc.convert(req()).await.unwrap();
+        assert_eq!(attempts(res.metadata()), Some(3));
+        assert_eq!(svc.as_inner().calls.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[tokio::test]
+async fn other_codes_fail_at_once() {
+    let svc = Retrying::new(Flaky::new(Code::Internal, 1), retry(3));
+    let e: Status = svc.convert(req()).await.unwrap_err();
+    assert_eq!(e.code(), Code::Internal);
+    assert_eq!(attempts(e.metadata()), Some(1));
+}
+
+#[tokio::test]
+async fn codes_are_configurable() {
+    let svc = Retrying::new(Flaky::new(Code::Internal, 1), retry(3)).codes([Code::Internal]);
+    let res: Response<ConvertResponse> = svc.convert(req()).await.unwrap();
+    assert_eq!(attempts(res.metadata()), Some(2));
+
+    let svc = Retrying::new(Flaky::new(Code::Unavailable, 1), retry(3)).codes([]);
+    let e: Status = svc.convert(req()).await.unwrap_err();
+    assert_eq!(attempts(e.metadata()), Some(1));
+}
+
+#[tokio::test]
+async fn retries_end_at_the_max() {
+    let svc = Retrying::new(Flaky::new(Code::Unavailable, 10), retry(2));
+    let e: Status = svc.convert(req()).await.unwrap_err();
+    assert_eq!(e.code(), Code::Unavailable);
+    assert_eq!(attempts(e.metadata()), Some(3));
+}
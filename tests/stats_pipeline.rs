@@ -0,0 +1,52 @@
+use core::time::Duration;
+use std::time::SystemTime;
+
+use rs_perf_test_helper::stats::pipeline::{PipelineStats, Stage};
+
+use helper::proto::buffer::v1::res_buf::GetResponse;
+use rs_perf_test_helper::rpc::perf::helper;
+
+fn at(t0: SystemTime, ms: u64) -> Option<prost_types::Timestamp> {
+    Some((t0 + Duration::from_millis(ms)).into())
+}
+
+#[test]
+fn stages_are_recorded() {
+    let mut p: PipelineStats = PipelineStats::new().unwrap();
+    let t0: SystemTime = SystemTime::now();
+    let gr = GetResponse {
+        res: None,
+        received: at(t0, 0),
+        saved: at(t0, 1),
+        loaded: at(t0, 11),
+        converted: at(t0, 111),
+        set: at(t0, 112),
+    };
+    p.ingest(&gr, t0 + Duration::from_millis(120));
+
+    let ms = |stage: Stage| p.get(stage).unwrap().max().as_millis();
+    assert_eq!(ms(Stage::Save), 1);
+    assert_eq!(ms(Stage::QueueWait), 10);
+    assert_eq!(ms(Stage::Convert), 100);
+    assert_eq!(ms(Stage::Set), 1);
+    assert_eq!(ms(Stage::Delivery), 8);
+    assert_eq!(ms(Stage::EndToEnd), 120);
+    assert_eq!(p.as_skewed(), 0);
+}
+
+#[test]
+fn missing_and_skewed_stages_are_skipped() {
+    let mut p: PipelineStats = PipelineStats::new().unwrap();
+    let t0: SystemTime = SystemTime::now();
+    let gr = GetResponse {
+        received: at(t0, 5),
+        saved: at(t0, 0),
+        ..Default::default()
+    };
+    p.ingest(&gr, t0 + Duration::from_millis(10));
+
+    assert!(p.get(Stage::QueueWait).unwrap().is_empty());
+    assert!(p.get(Stage::Save).unwrap().is_empty());
+    assert_eq!(p.get(Stage::EndToEnd).unwrap().len(), 1);
+    assert_eq!(p.as_skewed(), 1);
+}
@@ -0,0 +1,40 @@
+use rs_perf_test_helper::uuid::{Seeded, Uuid};
+
+use helper::proto::common::v1::Uuid as Cuid;
+use rs_perf_test_helper::rpc::perf::helper;
+
+fn version(u: Uuid) -> u128 {
+    (u.as_u128() >> 76) & 0xf
+}
+
+#[test]
+fn seeded_ids_repeat() {
+    let a: Vec<Uuid> = Seeded::new(42).take(16).collect();
+    let b: Vec<Uuid> = Seeded::new(42).take(16).collect();
+    let c: Vec<Uuid> = Seeded::new(43).take(16).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert!(a.iter().all(|u| version(*u) == 4));
+    assert!(a.iter().all(|u| (u.as_u128() >> 62) & 0b11 == 0b10));
+}
+
+#[test]
+fn ids_survive_the_proto() {
+    let u: Uuid = Uuid::from_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+    let c: Cuid = u.into();
+    assert_eq!((c.hi, c.lo), (0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210));
+    assert_eq!(Uuid::from(c).as_u128(), u.as_u128());
+}
+
+#[cfg(feature = "uv7")]
+#[test]
+fn v7_ids_are_ordered_by_creation() {
+    use std::collections::BTreeMap;
+
+    let ids: Vec<Uuid> = (0..1024).map(|_| Uuid::now_v7()).collect();
+    assert!(ids.iter().all(|u| version(*u) == 7));
+
+    let m: BTreeMap<Uuid, usize> = ids.iter().enumerate().map(|(i, u)| (*u, i)).collect();
+    let order: Vec<usize> = m.into_values().collect();
+    assert_eq!(order, (0..1024).collect::<Vec<_>>());
+}
@@ -0,0 +1,16 @@
+//! Sets the seed of the whole process; kept in its own binary so that no other test
+//! generates ids at the same time.
+
+use rs_perf_test_helper::uuid::{self, Seeded, Uuid};
+
+#[test]
+fn generate_follows_the_seed() {
+    uuid::seed(Some(7));
+    let a: Vec<Uuid> = (0..8).map(|_| Uuid::generate()).collect();
+    uuid::seed(Some(7));
+    let b: Vec<Uuid> = (0..8).map(|_| Uuid::generate()).collect();
+    uuid::seed(None);
+    assert_eq!(a, b);
+    assert_eq!(a, Seeded::new(7).take(8).collect::<Vec<_>>());
+    assert_ne!(Uuid::generate(), Uuid::generate());
+}